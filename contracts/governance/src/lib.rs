@@ -0,0 +1,1141 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solearn_token::{MintConfig, RateLimit};
+use solearn_vote_escrow::{Escrow, Lock};
+
+declare_id!("LearnGovernanceProgram111111111111111111111");
+
+/// LEARN holder governance. Each governance controls a signer PDA,
+/// `[b"governance-signer", governance]`, which is handed the authority of a
+/// solearn_token `MintConfig`, the solearn_learning `ProgramState` or
+/// individual courses. Holders deposit LEARN or lock it in the
+/// solearn_vote_escrow program to get voting power, propose parameter
+/// changes and execute passed proposals after the execution delay.
+#[program]
+pub mod solearn_governance {
+    use super::*;
+
+    /// Create the governance for a LEARN mint and its deposit vault. Only
+    /// the authority of the mint can create it, so nobody else can claim a
+    /// mint's governance with a config of their choosing.
+    pub fn create_governance(
+        ctx: Context<CreateGovernance>,
+        config: GovernanceConfig,
+    ) -> Result<()> {
+        config.validate()?;
+
+        let governance = &mut ctx.accounts.governance;
+        governance.mint = ctx.accounts.mint.key();
+        governance.vault = ctx.accounts.vault.key();
+        governance.config = config;
+        governance.proposal_count = 0;
+        governance.bump = *ctx.bumps.get("governance").unwrap();
+        governance.signer_bump = *ctx.bumps.get("governance_signer").unwrap();
+
+        msg!(
+            "Governance created for mint {} with quorum {} and voting period {}s",
+            governance.mint,
+            config.quorum_votes,
+            config.voting_period
+        );
+
+        Ok(())
+    }
+
+    /// Deposit LEARN into the governance vault. Deposited tokens are the
+    /// voter's voting power.
+    pub fn deposit_votes(ctx: Context<DepositVotes>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let voter_record = &mut ctx.accounts.voter_record;
+        if voter_record.voter == Pubkey::default() {
+            voter_record.governance = ctx.accounts.governance.key();
+            voter_record.voter = ctx.accounts.voter.key();
+            voter_record.bump = *ctx.bumps.get("voter_record").unwrap();
+        }
+        voter_record.deposited_amount = voter_record.deposited_amount.checked_add(amount).unwrap();
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.voter_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.voter.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("Deposited {} votes for {}", amount, voter_record.voter);
+
+        Ok(())
+    }
+
+    /// Withdraw deposited LEARN once every proposal the voter voted on has
+    /// finished voting
+    pub fn withdraw_votes(ctx: Context<WithdrawVotes>, amount: u64) -> Result<()> {
+        let voter_record = &mut ctx.accounts.voter_record;
+        let now = Clock::get()?.unix_timestamp;
+
+        if now < voter_record.voting_locked_until {
+            return Err(ErrorCode::VotesLocked.into());
+        }
+        if amount == 0 || amount > voter_record.deposited_amount {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+        voter_record.deposited_amount -= amount;
+
+        let governance = &ctx.accounts.governance;
+        let seeds = &[
+            b"governance".as_ref(),
+            governance.mint.as_ref(),
+            &[governance.bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.voter_token_account.to_account_info(),
+            authority: governance.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("Withdrew {} votes for {}", amount, voter_record.voter);
+
+        Ok(())
+    }
+
+    /// Create a proposal. The proposer needs at least the configured
+    /// proposal threshold in deposited votes. Voting opens immediately.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        description: String,
+        action: ProposalAction,
+    ) -> Result<()> {
+        if description.len() > Proposal::MAX_DESCRIPTION_LEN {
+            return Err(ErrorCode::DescriptionTooLong.into());
+        }
+        action.validate()?;
+
+        let governance = &mut ctx.accounts.governance;
+        if ctx.accounts.voter_record.deposited_amount < governance.config.proposal_threshold {
+            return Err(ErrorCode::InsufficientVotingPower.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let voting_ends_at = now.checked_add(governance.config.voting_period).unwrap();
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.governance = governance.key();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.index = governance.proposal_count;
+        proposal.description = description;
+        proposal.action = action;
+        proposal.yes_votes = 0;
+        proposal.no_votes = 0;
+        proposal.quorum_votes = governance.config.quorum_votes;
+        proposal.voting_starts_at = now;
+        proposal.voting_ends_at = voting_ends_at;
+        proposal.executable_at = voting_ends_at
+            .checked_add(governance.config.execution_delay)
+            .unwrap();
+        proposal.executed = false;
+        proposal.bump = *ctx.bumps.get("proposal").unwrap();
+
+        governance.proposal_count += 1;
+
+        emit!(ProposalCreated {
+            governance: proposal.governance,
+            proposal: proposal.key(),
+            proposer: proposal.proposer,
+            voting_ends_at,
+        });
+
+        msg!("Proposal {} created", proposal.index);
+
+        Ok(())
+    }
+
+    /// Vote on a proposal with all deposited votes plus the voter's veLEARN
+    /// power when voting started. Deposits stay locked until voting on the
+    /// proposal ends. A direct vote replaces a vote the voter's delegate
+    /// already cast for them.
+    pub fn cast_vote(ctx: Context<CastVote>, approve: bool) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let voter = ctx.accounts.voter.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        if now >= proposal.voting_ends_at {
+            return Err(ErrorCode::VotingClosed.into());
+        }
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        if vote_record.proposal != Pubkey::default() {
+            if vote_record.delegate == Pubkey::default() {
+                return Err(ErrorCode::AlreadyVoted.into());
+            }
+            proposal.remove_votes(vote_record.approve, vote_record.weight);
+            msg!("Overriding the vote cast by delegate {}", vote_record.delegate);
+        }
+
+        let weight = voting_weight(
+            ctx.accounts.voter_record.as_mut(),
+            ctx.accounts.vote_escrow.as_ref(),
+            ctx.accounts.escrow_lock.as_ref(),
+            proposal,
+        )?;
+        proposal.add_votes(approve, weight);
+
+        vote_record.proposal = proposal.key();
+        vote_record.voter = voter;
+        vote_record.delegate = Pubkey::default();
+        vote_record.approve = approve;
+        vote_record.weight = weight;
+        vote_record.bump = *ctx.bumps.get("vote_record").unwrap();
+
+        emit!(VoteCast {
+            proposal: proposal.key(),
+            voter,
+            delegate: None,
+            approve,
+            weight,
+        });
+
+        msg!(
+            "Vote cast on proposal {}: {} with weight {}",
+            proposal.index,
+            if approve { "yes" } else { "no" },
+            weight
+        );
+
+        Ok(())
+    }
+
+    /// Delegate the caller's voting weight, deposited votes and veLEARN, to
+    /// another wallet. Delegating again replaces the previous delegate.
+    pub fn delegate_votes(ctx: Context<DelegateVotes>, delegate: Pubkey) -> Result<()> {
+        let delegator = ctx.accounts.delegator.key();
+        if delegate == delegator || delegate == Pubkey::default() {
+            return Err(ErrorCode::InvalidDelegate.into());
+        }
+
+        let delegation = &mut ctx.accounts.delegation;
+        delegation.governance = ctx.accounts.governance.key();
+        delegation.delegator = delegator;
+        delegation.delegate = delegate;
+        delegation.bump = *ctx.bumps.get("delegation").unwrap();
+
+        emit!(VotesDelegated {
+            governance: delegation.governance,
+            delegator,
+            delegate,
+        });
+
+        msg!("{} delegated votes to {}", delegator, delegate);
+
+        Ok(())
+    }
+
+    /// Revoke the caller's delegation. Votes the delegate already cast stay
+    /// counted; the delegator can still override them by voting directly.
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        let delegation = &ctx.accounts.delegation;
+
+        emit!(DelegationRevoked {
+            governance: delegation.governance,
+            delegator: delegation.delegator,
+            delegate: delegation.delegate,
+        });
+
+        msg!("{} revoked delegation to {}", delegation.delegator, delegation.delegate);
+
+        Ok(())
+    }
+
+    /// Vote on a proposal with a delegator's weight. The vote record is
+    /// keyed by the delegator, so their weight is counted at most once per
+    /// proposal no matter how often the delegation changes, and not at all
+    /// once they voted directly.
+    pub fn cast_delegated_vote(ctx: Context<CastDelegatedVote>, approve: bool) -> Result<()> {
+        let proposal = &mut ctx.accounts.proposal;
+        let delegation = &ctx.accounts.delegation;
+        let now = Clock::get()?.unix_timestamp;
+
+        if now >= proposal.voting_ends_at {
+            return Err(ErrorCode::VotingClosed.into());
+        }
+
+        let weight = voting_weight(
+            ctx.accounts.voter_record.as_mut(),
+            ctx.accounts.vote_escrow.as_ref(),
+            ctx.accounts.escrow_lock.as_ref(),
+            proposal,
+        )?;
+        proposal.add_votes(approve, weight);
+
+        let vote_record = &mut ctx.accounts.vote_record;
+        vote_record.proposal = proposal.key();
+        vote_record.voter = delegation.delegator;
+        vote_record.delegate = delegation.delegate;
+        vote_record.approve = approve;
+        vote_record.weight = weight;
+        vote_record.bump = *ctx.bumps.get("vote_record").unwrap();
+
+        emit!(VoteCast {
+            proposal: proposal.key(),
+            voter: delegation.delegator,
+            delegate: Some(delegation.delegate),
+            approve,
+            weight,
+        });
+
+        msg!(
+            "Delegated vote cast on proposal {} for {}: {} with weight {}",
+            proposal.index,
+            delegation.delegator,
+            if approve { "yes" } else { "no" },
+            weight
+        );
+
+        Ok(())
+    }
+
+    /// Execute a passed proposal once its execution delay has elapsed.
+    /// Anyone may call this; the accounts the action needs are passed as
+    /// remaining accounts, see `ProposalAction`.
+    pub fn execute_proposal<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteProposal<'info>>,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let proposal = &mut ctx.accounts.proposal;
+
+        if proposal.executed {
+            return Err(ErrorCode::ProposalAlreadyExecuted.into());
+        }
+        if now < proposal.voting_ends_at {
+            return Err(ErrorCode::VotingInProgress.into());
+        }
+        if !proposal.passed() {
+            return Err(ErrorCode::ProposalNotPassed.into());
+        }
+        if now < proposal.executable_at {
+            return Err(ErrorCode::ExecutionDelayActive.into());
+        }
+
+        // Mark executed and write the flag to the account before invoking.
+        // Anchor only serializes accounts on exit, so without this a re-entrant
+        // call from the invoked program would still see the proposal unexecuted.
+        proposal.executed = true;
+        proposal.exit(&crate::ID)?;
+
+        match proposal.action.clone() {
+            ProposalAction::UpdateGovernanceConfig { config } => {
+                ctx.accounts.governance.config = config;
+                msg!("Governance config updated");
+            }
+            action => execute_cpi_action(
+                &ctx.accounts.governance,
+                ctx.accounts.governance_signer.to_account_info(),
+                ctx.remaining_accounts,
+                action,
+            )?,
+        }
+
+        emit!(ProposalExecuted {
+            governance: ctx.accounts.governance.key(),
+            proposal: ctx.accounts.proposal.key(),
+        });
+
+        msg!("Proposal {} executed", ctx.accounts.proposal.index);
+
+        Ok(())
+    }
+}
+
+/// Voting weight of a voter on `proposal`: their deposited votes plus the
+/// veLEARN power of their lock when voting started. Deposits are locked
+/// until voting on the proposal ends and the lock must outlast it, so the
+/// same tokens cannot be counted again from another wallet.
+fn voting_weight(
+    voter_record: Option<&mut Account<VoterRecord>>,
+    vote_escrow: Option<&Account<Escrow>>,
+    escrow_lock: Option<&Account<Lock>>,
+    proposal: &Proposal,
+) -> Result<u64> {
+    let mut weight: u64 = 0;
+    if let Some(voter_record) = voter_record {
+        weight = voter_record.deposited_amount;
+        voter_record.voting_locked_until = voter_record.voting_locked_until.max(proposal.voting_ends_at);
+    }
+    if let (Some(escrow), Some(lock)) = (vote_escrow, escrow_lock) {
+        if lock.unlock_at < proposal.voting_ends_at {
+            return Err(ErrorCode::LockExpiresDuringVoting.into());
+        }
+        // Locks created or changed after voting started have no power at
+        // its start
+        let power = lock.voting_power_at(escrow, proposal.voting_starts_at)?;
+        weight = weight.checked_add(power).unwrap();
+    }
+    if weight == 0 {
+        return Err(ErrorCode::InsufficientVotingPower.into());
+    }
+
+    Ok(weight)
+}
+
+/// Carry out a proposal action in another program, signing the CPI with the
+/// governance signer PDA
+fn execute_cpi_action<'info>(
+    governance: &Account<'info, Governance>,
+    governance_signer: AccountInfo<'info>,
+    accounts: &[AccountInfo<'info>],
+    action: ProposalAction,
+) -> Result<()> {
+    let governance_key = governance.key();
+    let seeds = &[
+        b"governance-signer".as_ref(),
+        governance_key.as_ref(),
+        &[governance.signer_bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    match action {
+        ProposalAction::UpdateMintConfig {
+            new_supply_cap,
+            new_rate_limit,
+            pause_minting,
+            new_compliance_authority,
+        } => {
+            let [mint_config, mint, token_program] = token_accounts(governance, accounts)?;
+            let cpi_accounts = solearn_token::cpi::accounts::UpdateMintConfig {
+                mint_config,
+                mint,
+                authority: governance_signer,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+            solearn_token::cpi::update_mint_config(
+                cpi_ctx,
+                new_supply_cap,
+                new_rate_limit,
+                pause_minting,
+                new_compliance_authority,
+            )
+        }
+        ProposalAction::AcceptMintAuthority => {
+            let [mint_config, mint, token_program] = token_accounts(governance, accounts)?;
+            let cpi_accounts = solearn_token::cpi::accounts::AcceptAuthority {
+                mint_config,
+                mint,
+                pending_authority: governance_signer,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer);
+            solearn_token::cpi::accept_authority(cpi_ctx)
+        }
+        ProposalAction::UpdateProgramConfig {
+            program_state,
+            pause_program,
+            paused_operations,
+        } => {
+            let [program_state, learning_program] = learning_accounts(accounts, Some(program_state))?;
+            let cpi_accounts = solearn_learning::cpi::accounts::UpdateProgramConfig {
+                program_state,
+                authority: governance_signer,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(learning_program, cpi_accounts, signer);
+            solearn_learning::cpi::update_program_config(cpi_ctx, pause_program, paused_operations)
+        }
+        ProposalAction::AcceptProgramAuthority { program_state } => {
+            let [program_state, learning_program] = learning_accounts(accounts, Some(program_state))?;
+            let cpi_accounts = solearn_learning::cpi::accounts::AcceptAuthority {
+                program_state,
+                pending_authority: governance_signer,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(learning_program, cpi_accounts, signer);
+            solearn_learning::cpi::accept_authority(cpi_ctx)
+        }
+        ProposalAction::UpdateCourse {
+            course,
+            title,
+            description,
+            reward_amount,
+            required_score,
+            active,
+        } => {
+            // The course is tied to its program state by the learning program
+            let [program_state, learning_program] = learning_accounts(accounts, None)?;
+            let course_info = accounts
+                .get(2)
+                .filter(|info| info.key() == course)
+                .ok_or(ErrorCode::InvalidExecutionAccounts)?
+                .clone();
+            let institution = accounts
+                .get(3)
+                .ok_or(ErrorCode::InvalidExecutionAccounts)?
+                .clone();
+            let cpi_accounts = solearn_learning::cpi::accounts::UpdateCourse {
+                program_state,
+                course: course_info,
+                institution,
+                course_version: None,
+                authority: governance_signer,
+                system_program: None,
+            };
+            let cpi_ctx = CpiContext::new_with_signer(learning_program, cpi_accounts, signer);
+            solearn_learning::cpi::update_course(
+                cpi_ctx,
+                title,
+                description,
+                reward_amount,
+                required_score,
+                active,
+                None,
+                None,
+            )
+        }
+        ProposalAction::UpdateGovernanceConfig { .. } => {
+            Err(ErrorCode::InvalidProposalAction.into())
+        }
+    }
+}
+
+/// `[mint_config, mint, solearn_token program]` for token actions
+fn token_accounts<'info>(
+    governance: &Governance,
+    accounts: &[AccountInfo<'info>],
+) -> Result<[AccountInfo<'info>; 3]> {
+    match accounts {
+        [mint_config, mint, token_program, ..]
+            if mint.key() == governance.mint && token_program.key() == solearn_token::ID =>
+        {
+            Ok([mint_config.clone(), mint.clone(), token_program.clone()])
+        }
+        _ => Err(ErrorCode::InvalidExecutionAccounts.into()),
+    }
+}
+
+/// `[program_state, solearn_learning program]` for learning actions. The
+/// program state must be `expected_program_state` when the action names one.
+fn learning_accounts<'info>(
+    accounts: &[AccountInfo<'info>],
+    expected_program_state: Option<Pubkey>,
+) -> Result<[AccountInfo<'info>; 2]> {
+    match accounts {
+        [program_state, learning_program, ..]
+            if learning_program.key() == solearn_learning::ID
+                && expected_program_state
+                    .filter(|expected| *expected != program_state.key())
+                    .is_none() =>
+        {
+            Ok([program_state.clone(), learning_program.clone()])
+        }
+        _ => Err(ErrorCode::InvalidExecutionAccounts.into()),
+    }
+}
+
+#[derive(Accounts)]
+pub struct CreateGovernance<'info> {
+    #[account(
+        init,
+        seeds = [b"governance", mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Governance::LEN
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        seeds = [b"governance-signer", governance.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: The PDA that signs executed proposals on behalf of the governance
+    pub governance_signer: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        seeds = [b"governance-vault", governance.key().as_ref()],
+        bump,
+        payer = payer,
+        token::mint = mint,
+        token::authority = governance,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// The solearn_token MintConfig of `mint`, passed when the mint is
+    /// managed by solearn_token
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        seeds::program = solearn_token::ID,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// The SPL mint authority, or the MintConfig authority of a mint
+    /// managed by solearn_token
+    #[account(
+        constraint = solearn_token::controls_mint(&mint, mint_config.as_deref(), &authority.key())
+            @ ErrorCode::NotMintAuthority,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct DepositVotes<'info> {
+    #[account(
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+        has_one = vault,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump,
+        payer = voter,
+        space = 8 + VoterRecord::LEN
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = voter_token_account.mint == governance.mint,
+        constraint = voter_token_account.owner == voter.key(),
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawVotes<'info> {
+    #[account(
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+        has_one = vault,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = voter_token_account.mint == governance.mint,
+        constraint = voter_token_account.owner == voter.key(),
+    )]
+    pub voter_token_account: Account<'info, TokenAccount>,
+
+    pub voter: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init,
+        seeds = [b"proposal", governance.key().as_ref(), &governance.proposal_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = 8 + Proposal::LEN
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"voter", governance.key().as_ref(), proposer.key().as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Account<'info, VoterRecord>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = governance,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), voter.key().as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Option<Account<'info, VoterRecord>>,
+
+    /// veLEARN escrow of the governance mint, required with `escrow_lock`
+    #[account(constraint = vote_escrow.mint == governance.mint @ ErrorCode::InvalidVoteEscrow)]
+    pub vote_escrow: Option<Account<'info, Escrow>>,
+
+    #[account(
+        constraint = vote_escrow.is_some() @ ErrorCode::InvalidVoteEscrow,
+        constraint = escrow_lock.escrow == vote_escrow.as_ref().unwrap().key() @ ErrorCode::InvalidVoteEscrow,
+        constraint = escrow_lock.owner == voter.key() @ ErrorCode::InvalidVoteEscrow,
+    )]
+    pub escrow_lock: Option<Account<'info, Lock>>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump,
+        payer = voter,
+        space = 8 + VoteRecord::LEN
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotes<'info> {
+    #[account(
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"delegation", governance.key().as_ref(), delegator.key().as_ref()],
+        bump,
+        payer = delegator,
+        space = 8 + Delegation::LEN
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(
+        mut,
+        seeds = [b"delegation", delegation.governance.as_ref(), delegator.key().as_ref()],
+        bump = delegation.bump,
+        has_one = delegator,
+        close = delegator,
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CastDelegatedVote<'info> {
+    #[account(
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = governance,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"delegation", governance.key().as_ref(), delegation.delegator.as_ref()],
+        bump = delegation.bump,
+        has_one = delegate @ ErrorCode::InvalidDelegate,
+    )]
+    pub delegation: Account<'info, Delegation>,
+
+    #[account(
+        mut,
+        seeds = [b"voter", governance.key().as_ref(), delegation.delegator.as_ref()],
+        bump = voter_record.bump,
+    )]
+    pub voter_record: Option<Account<'info, VoterRecord>>,
+
+    /// veLEARN escrow of the governance mint, required with `escrow_lock`
+    #[account(constraint = vote_escrow.mint == governance.mint @ ErrorCode::InvalidVoteEscrow)]
+    pub vote_escrow: Option<Account<'info, Escrow>>,
+
+    #[account(
+        constraint = vote_escrow.is_some() @ ErrorCode::InvalidVoteEscrow,
+        constraint = escrow_lock.escrow == vote_escrow.as_ref().unwrap().key() @ ErrorCode::InvalidVoteEscrow,
+        constraint = escrow_lock.owner == delegation.delegator @ ErrorCode::InvalidVoteEscrow,
+    )]
+    pub escrow_lock: Option<Account<'info, Lock>>,
+
+    #[account(
+        init,
+        seeds = [b"vote", proposal.key().as_ref(), delegation.delegator.as_ref()],
+        bump,
+        payer = delegate,
+        space = 8 + VoteRecord::LEN
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(mut)]
+    pub delegate: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"governance", governance.mint.as_ref()],
+        bump = governance.bump,
+    )]
+    pub governance: Account<'info, Governance>,
+
+    #[account(
+        mut,
+        seeds = [b"governance-signer", governance.key().as_ref()],
+        bump = governance.signer_bump,
+    )]
+    /// CHECK: The PDA that signs for the governance in CPIs
+    pub governance_signer: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", governance.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = governance,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+/// Voting rules of a governance
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default)]
+pub struct GovernanceConfig {
+    /// Minimum yes plus no votes for a proposal to pass
+    pub quorum_votes: u64,
+    /// Seconds a proposal is open for voting
+    pub voting_period: i64,
+    /// Seconds between the end of voting and execution
+    pub execution_delay: i64,
+    /// Deposited votes required to create a proposal
+    pub proposal_threshold: u64,
+}
+
+impl GovernanceConfig {
+    pub const LEN: usize = 8 + 8 + 8 + 8;
+
+    pub fn validate(&self) -> Result<()> {
+        if self.quorum_votes == 0 || self.voting_period <= 0 || self.execution_delay < 0 {
+            return Err(ErrorCode::InvalidGovernanceConfig.into());
+        }
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Governance {
+    pub mint: Pubkey,                  // 32 bytes
+    pub vault: Pubkey,                 // 32 bytes
+    pub config: GovernanceConfig,      // GovernanceConfig::LEN bytes
+    pub proposal_count: u64,           // 8 bytes
+    pub bump: u8,                      // 1 byte
+    pub signer_bump: u8,               // 1 byte
+}
+
+impl Governance {
+    pub const LEN: usize = 32 + 32 + GovernanceConfig::LEN + 8 + 1 + 1;
+}
+
+#[account]
+#[derive(Default)]
+pub struct VoterRecord {
+    pub governance: Pubkey,            // 32 bytes
+    pub voter: Pubkey,                 // 32 bytes
+    pub deposited_amount: u64,         // 8 bytes
+    pub voting_locked_until: i64,      // 8 bytes, latest voting end of proposals voted on
+    pub bump: u8,                      // 1 byte
+}
+
+impl VoterRecord {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 1;
+}
+
+#[account]
+#[derive(Default)]
+pub struct VoteRecord {
+    pub proposal: Pubkey,              // 32 bytes
+    pub voter: Pubkey,                 // 32 bytes, whose weight was counted
+    pub delegate: Pubkey,              // 32 bytes, default for direct votes
+    pub approve: bool,                 // 1 byte
+    pub weight: u64,                   // 8 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 32 + 32 + 32 + 1 + 8 + 1;
+}
+
+#[account]
+#[derive(Default)]
+pub struct Delegation {
+    pub governance: Pubkey,            // 32 bytes
+    pub delegator: Pubkey,             // 32 bytes
+    pub delegate: Pubkey,              // 32 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl Delegation {
+    pub const LEN: usize = 32 + 32 + 32 + 1;
+}
+
+#[account]
+pub struct Proposal {
+    pub governance: Pubkey,            // 32 bytes
+    pub proposer: Pubkey,              // 32 bytes
+    pub index: u64,                    // 8 bytes
+    pub description: String,           // 4 + 200 = 204 bytes
+    pub action: ProposalAction,        // ProposalAction::MAX_LEN bytes
+    pub yes_votes: u64,                // 8 bytes
+    pub no_votes: u64,                 // 8 bytes
+    pub quorum_votes: u64,             // 8 bytes, snapshot of the config at creation
+    pub voting_starts_at: i64,         // 8 bytes
+    pub voting_ends_at: i64,           // 8 bytes
+    pub executable_at: i64,            // 8 bytes
+    pub executed: bool,                // 1 byte
+    pub bump: u8,                      // 1 byte
+}
+
+impl Proposal {
+    pub const MAX_DESCRIPTION_LEN: usize = 200;
+    pub const LEN: usize = 32 + 32 + 8 + (4 + Self::MAX_DESCRIPTION_LEN) + ProposalAction::MAX_LEN
+        + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 1;
+
+    /// Whether the proposal reached quorum with more yes than no votes
+    pub fn passed(&self) -> bool {
+        let total_votes = self.yes_votes.saturating_add(self.no_votes);
+        total_votes >= self.quorum_votes && self.yes_votes > self.no_votes
+    }
+
+    pub fn add_votes(&mut self, approve: bool, weight: u64) {
+        if approve {
+            self.yes_votes = self.yes_votes.checked_add(weight).unwrap();
+        } else {
+            self.no_votes = self.no_votes.checked_add(weight).unwrap();
+        }
+    }
+
+    pub fn remove_votes(&mut self, approve: bool, weight: u64) {
+        if approve {
+            self.yes_votes -= weight;
+        } else {
+            self.no_votes -= weight;
+        }
+    }
+}
+
+/// What a proposal does when executed, and the remaining accounts
+/// `execute_proposal` expects for it
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub enum ProposalAction {
+    /// `solearn_token::update_mint_config`.
+    /// Accounts: `[mint_config, mint, solearn_token program]`
+    UpdateMintConfig {
+        new_supply_cap: Option<u64>,
+        new_rate_limit: Option<RateLimit>,
+        pause_minting: Option<bool>,
+        new_compliance_authority: Option<Pubkey>,
+    },
+    /// `solearn_token::accept_authority` for a transfer proposed to the
+    /// governance signer.
+    /// Accounts: `[mint_config, mint, solearn_token program]`
+    AcceptMintAuthority,
+    /// `solearn_learning::update_program_config` on the tenant state
+    /// `program_state`.
+    /// Accounts: `[program_state, solearn_learning program]`
+    UpdateProgramConfig {
+        program_state: Pubkey,
+        pause_program: Option<bool>,
+        paused_operations: Option<u8>,
+    },
+    /// `solearn_learning::accept_authority` on the tenant state
+    /// `program_state` for a transfer proposed to the governance signer.
+    /// Accounts: `[program_state, solearn_learning program]`
+    AcceptProgramAuthority { program_state: Pubkey },
+    /// `solearn_learning::update_course` on a course owned by the governance
+    /// signer or in an institution it manages.
+    /// Accounts: `[program_state, solearn_learning program, course, institution]`,
+    /// all writable but the program
+    UpdateCourse {
+        course: Pubkey,
+        title: Option<String>,
+        description: Option<String>,
+        reward_amount: Option<u64>,
+        required_score: Option<u8>,
+        active: Option<bool>,
+    },
+    /// Replace this governance's voting rules. No accounts.
+    UpdateGovernanceConfig { config: GovernanceConfig },
+}
+
+impl ProposalAction {
+    /// Largest variant, `UpdateCourse`: course, title (100), description
+    /// (200), reward, score and active
+    pub const MAX_LEN: usize = 1 + 32 + (1 + 4 + 100) + (1 + 4 + 200) + (1 + 8) + (1 + 1) + (1 + 1);
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ProposalAction::UpdateCourse {
+                title, description, ..
+            } if matches!(title, Some(title) if title.len() > 100)
+                || matches!(description, Some(description) if description.len() > 200) =>
+            {
+                return Err(ErrorCode::InvalidProposalAction.into());
+            }
+            ProposalAction::UpdateGovernanceConfig { config } => config.validate()?,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub governance: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub voting_ends_at: i64,
+}
+
+#[event]
+pub struct VoteCast {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub delegate: Option<Pubkey>,
+    pub approve: bool,
+    pub weight: u64,
+}
+
+#[event]
+pub struct VotesDelegated {
+    pub governance: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct DelegationRevoked {
+    pub governance: Pubkey,
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub governance: Pubkey,
+    pub proposal: Pubkey,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Invalid governance config")]
+    InvalidGovernanceConfig,
+
+    #[msg("Amount must be greater than zero and within the deposit")]
+    InvalidAmount,
+
+    #[msg("Deposited votes are locked until voting ends on every voted proposal")]
+    VotesLocked,
+
+    #[msg("Not enough deposited votes")]
+    InsufficientVotingPower,
+
+    #[msg("Proposal description too long")]
+    DescriptionTooLong,
+
+    #[msg("Invalid proposal action")]
+    InvalidProposalAction,
+
+    #[msg("Voting on this proposal has closed")]
+    VotingClosed,
+
+    #[msg("Voting on this proposal is still in progress")]
+    VotingInProgress,
+
+    #[msg("Proposal did not pass")]
+    ProposalNotPassed,
+
+    #[msg("Execution delay has not elapsed")]
+    ExecutionDelayActive,
+
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Missing or invalid accounts for the proposal action")]
+    InvalidExecutionAccounts,
+
+    #[msg("Vote escrow lock does not belong to the voter or the governance mint")]
+    InvalidVoteEscrow,
+
+    #[msg("Already voted on this proposal")]
+    AlreadyVoted,
+
+    #[msg("Invalid delegate")]
+    InvalidDelegate,
+
+    #[msg("Vote escrow lock expires before voting on the proposal ends")]
+    LockExpiresDuringVoting,
+
+    #[msg("Signer is not the mint authority")]
+    NotMintAuthority,
+}
@@ -0,0 +1,922 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_governance::{GovernanceConfig, ProposalAction};
+
+const QUORUM_VOTES: u64 = 500;
+const VOTING_PERIOD: i64 = 100;
+const EXECUTION_DELAY: i64 = 50;
+const PROPOSAL_THRESHOLD: u64 = 100;
+const MIN_LOCK_DURATION: i64 = 10;
+const MAX_LOCK_DURATION: i64 = 1_000;
+
+struct Fixture {
+    context: ProgramTestContext,
+    admin: Keypair,
+    governance: Pubkey,
+    governance_signer: Pubkey,
+    vault: Pubkey,
+    escrow: Pubkey,
+    escrow_vault: Pubkey,
+    program_state: Pubkey,
+    /// Voters and their token accounts, funded with 600, 300 and 100 LEARN
+    voters: Vec<(Keypair, Pubkey)>,
+    now: i64,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+impl Fixture {
+    /// Send a transaction at the fixture's current time
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = self.now;
+        self.context.set_sysvar(&clock);
+
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            all_signers.as_slice(),
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn voter_record(&self, voter: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"voter", self.governance.as_ref(), voter.as_ref()],
+            &solearn_governance::id(),
+        )
+        .0
+    }
+
+    /// Proposal action handing the fixture's learning program to governance
+    fn accept_program_authority(&self) -> ProposalAction {
+        ProposalAction::AcceptProgramAuthority {
+            program_state: self.program_state,
+        }
+    }
+
+    fn proposal(&self, index: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"proposal", self.governance.as_ref(), &index.to_le_bytes()],
+            &solearn_governance::id(),
+        )
+        .0
+    }
+
+    async fn deposit(&mut self, voter_index: usize, amount: u64) -> Result<(), BanksClientError> {
+        let voter = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::DepositVotes {
+                governance: self.governance,
+                voter_record: self.voter_record(&voter.pubkey()),
+                vault: self.vault,
+                voter_token_account: self.voters[voter_index].1,
+                voter: voter.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::DepositVotes { amount }.data(),
+        };
+        self.process(&[instruction], &voter, &[]).await
+    }
+
+    async fn withdraw(&mut self, voter_index: usize, amount: u64) -> Result<(), BanksClientError> {
+        let voter = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::WithdrawVotes {
+                governance: self.governance,
+                voter_record: self.voter_record(&voter.pubkey()),
+                vault: self.vault,
+                voter_token_account: self.voters[voter_index].1,
+                voter: voter.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::WithdrawVotes { amount }.data(),
+        };
+        self.process(&[instruction], &voter, &[]).await
+    }
+
+    async fn create_proposal(
+        &mut self,
+        voter_index: usize,
+        index: u64,
+        action: ProposalAction,
+    ) -> Result<(), BanksClientError> {
+        let proposer = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::CreateProposal {
+                governance: self.governance,
+                proposal: self.proposal(index),
+                voter_record: self.voter_record(&proposer.pubkey()),
+                proposer: proposer.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::CreateProposal {
+                description: "Hand the learning program to governance".to_string(),
+                action,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &proposer, &[]).await
+    }
+
+    async fn vote(&mut self, voter_index: usize, index: u64, approve: bool) -> Result<(), BanksClientError> {
+        let voter = self.voters[voter_index].0.insecure_clone();
+        let proposal = self.proposal(index);
+        let vote_record = Pubkey::find_program_address(
+            &[b"vote", proposal.as_ref(), voter.pubkey().as_ref()],
+            &solearn_governance::id(),
+        )
+        .0;
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::CastVote {
+                governance: self.governance,
+                proposal,
+                voter_record: Some(self.voter_record(&voter.pubkey())),
+                vote_escrow: None,
+                escrow_lock: None,
+                vote_record,
+                voter: voter.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::CastVote { approve }.data(),
+        };
+        self.process(&[instruction], &voter, &[]).await
+    }
+
+    fn delegation(&self, delegator: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"delegation", self.governance.as_ref(), delegator.as_ref()],
+            &solearn_governance::id(),
+        )
+        .0
+    }
+
+    async fn delegate(&mut self, voter_index: usize, delegate_index: usize) -> Result<(), BanksClientError> {
+        let delegator = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::DelegateVotes {
+                governance: self.governance,
+                delegation: self.delegation(&delegator.pubkey()),
+                delegator: delegator.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::DelegateVotes {
+                delegate: self.voters[delegate_index].0.pubkey(),
+            }
+            .data(),
+        };
+        self.process(&[instruction], &delegator, &[]).await
+    }
+
+    async fn revoke_delegation(&mut self, voter_index: usize) -> Result<(), BanksClientError> {
+        let delegator = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::RevokeDelegation {
+                delegation: self.delegation(&delegator.pubkey()),
+                delegator: delegator.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::RevokeDelegation {}.data(),
+        };
+        self.process(&[instruction], &delegator, &[]).await
+    }
+
+    /// Vote with the weight of `voter_index` as their delegate `delegate_index`
+    async fn delegated_vote(
+        &mut self,
+        delegate_index: usize,
+        voter_index: usize,
+        index: u64,
+        approve: bool,
+    ) -> Result<(), BanksClientError> {
+        let delegate = self.voters[delegate_index].0.insecure_clone();
+        let delegator = self.voters[voter_index].0.pubkey();
+        let proposal = self.proposal(index);
+        let vote_record = Pubkey::find_program_address(
+            &[b"vote", proposal.as_ref(), delegator.as_ref()],
+            &solearn_governance::id(),
+        )
+        .0;
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::CastDelegatedVote {
+                governance: self.governance,
+                proposal,
+                delegation: self.delegation(&delegator),
+                voter_record: Some(self.voter_record(&delegator)),
+                vote_escrow: None,
+                escrow_lock: None,
+                vote_record,
+                delegate: delegate.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::CastDelegatedVote { approve }.data(),
+        };
+        self.process(&[instruction], &delegate, &[]).await
+    }
+
+    fn escrow_lock(&self, owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"lock", self.escrow.as_ref(), owner.as_ref()],
+            &solearn_vote_escrow::id(),
+        )
+        .0
+    }
+
+    async fn create_lock(&mut self, voter_index: usize, amount: u64, unlock_at: i64) -> Result<(), BanksClientError> {
+        let owner = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vote_escrow::id(),
+            accounts: solearn_vote_escrow::accounts::CreateLock {
+                escrow: self.escrow,
+                lock: self.escrow_lock(&owner.pubkey()),
+                vault: self.escrow_vault,
+                owner_token_account: self.voters[voter_index].1,
+                owner: owner.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vote_escrow::instruction::CreateLock { amount, unlock_at }.data(),
+        };
+        self.process(&[instruction], &owner, &[]).await
+    }
+
+    async fn increase_lock_amount(&mut self, voter_index: usize, amount: u64) -> Result<(), BanksClientError> {
+        let owner = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vote_escrow::id(),
+            accounts: solearn_vote_escrow::accounts::UpdateLock {
+                escrow: self.escrow,
+                lock: self.escrow_lock(&owner.pubkey()),
+                vault: self.escrow_vault,
+                owner_token_account: self.voters[voter_index].1,
+                owner: owner.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vote_escrow::instruction::IncreaseLockAmount { amount }.data(),
+        };
+        self.process(&[instruction], &owner, &[]).await
+    }
+
+    async fn extend_lock(&mut self, voter_index: usize, unlock_at: i64) -> Result<(), BanksClientError> {
+        let owner = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vote_escrow::id(),
+            accounts: solearn_vote_escrow::accounts::ExtendLock {
+                escrow: self.escrow,
+                lock: self.escrow_lock(&owner.pubkey()),
+                owner: owner.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_vote_escrow::instruction::ExtendLock { unlock_at }.data(),
+        };
+        self.process(&[instruction], &owner, &[]).await
+    }
+
+    async fn withdraw_lock(&mut self, voter_index: usize) -> Result<(), BanksClientError> {
+        let owner = self.voters[voter_index].0.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vote_escrow::id(),
+            accounts: solearn_vote_escrow::accounts::Withdraw {
+                escrow: self.escrow,
+                lock: self.escrow_lock(&owner.pubkey()),
+                vault: self.escrow_vault,
+                owner_token_account: self.voters[voter_index].1,
+                owner: owner.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vote_escrow::instruction::Withdraw {}.data(),
+        };
+        self.process(&[instruction], &owner, &[]).await
+    }
+
+    /// Create a mint whose authority is the fixture's admin
+    async fn create_mint(&mut self) -> Pubkey {
+        let admin = self.admin.insecure_clone();
+        let mint = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let create_mint = [
+            system_instruction::create_account(
+                &admin.pubkey(),
+                &mint.pubkey(),
+                rent.minimum_balance(spl_token::state::Mint::LEN),
+                spl_token::state::Mint::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &admin.pubkey(), None, 6)
+                .unwrap(),
+        ];
+        self.process(&create_mint, &admin, &[&mint]).await.unwrap();
+        mint.pubkey()
+    }
+
+    /// Vote with the voter's veLEARN lock, plus their deposit if they have one
+    async fn vote_with_lock(&mut self, voter_index: usize, index: u64, approve: bool) -> Result<(), BanksClientError> {
+        let voter = self.voters[voter_index].0.insecure_clone();
+        let proposal = self.proposal(index);
+        let vote_record = Pubkey::find_program_address(
+            &[b"vote", proposal.as_ref(), voter.pubkey().as_ref()],
+            &solearn_governance::id(),
+        )
+        .0;
+        let voter_record = self.voter_record(&voter.pubkey());
+        let has_deposit = self
+            .context
+            .banks_client
+            .get_account(voter_record)
+            .await
+            .unwrap()
+            .is_some();
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts: solearn_governance::accounts::CastVote {
+                governance: self.governance,
+                proposal,
+                voter_record: has_deposit.then_some(voter_record),
+                vote_escrow: Some(self.escrow),
+                escrow_lock: Some(self.escrow_lock(&voter.pubkey())),
+                vote_record,
+                voter: voter.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_governance::instruction::CastVote { approve }.data(),
+        };
+        self.process(&[instruction], &voter, &[]).await
+    }
+
+    /// Execute a learning program proposal
+    async fn execute(&mut self, index: u64) -> Result<(), BanksClientError> {
+        let mut accounts = solearn_governance::accounts::ExecuteProposal {
+            governance: self.governance,
+            governance_signer: self.governance_signer,
+            proposal: self.proposal(index),
+        }
+        .to_account_metas(None);
+        accounts.push(AccountMeta::new(self.program_state, false));
+        accounts.push(AccountMeta::new_readonly(solearn_learning::id(), false));
+
+        let instruction = Instruction {
+            program_id: solearn_governance::id(),
+            accounts,
+            data: solearn_governance::instruction::ExecuteProposal {}.data(),
+        };
+        let admin = self.admin.insecure_clone();
+        self.process(&[instruction], &admin, &[]).await
+    }
+}
+
+/// Create the governance of `mint`, signed by `authority`
+fn create_governance(mint: &Pubkey, authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    let (governance, _) =
+        Pubkey::find_program_address(&[b"governance", mint.as_ref()], &solearn_governance::id());
+    Instruction {
+        program_id: solearn_governance::id(),
+        accounts: solearn_governance::accounts::CreateGovernance {
+            governance,
+            governance_signer: Pubkey::find_program_address(
+                &[b"governance-signer", governance.as_ref()],
+                &solearn_governance::id(),
+            )
+            .0,
+            vault: Pubkey::find_program_address(
+                &[b"governance-vault", governance.as_ref()],
+                &solearn_governance::id(),
+            )
+            .0,
+            mint: *mint,
+            mint_config: None,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_governance::instruction::CreateGovernance {
+            config: GovernanceConfig {
+                quorum_votes: QUORUM_VOTES,
+                voting_period: VOTING_PERIOD,
+                execution_delay: EXECUTION_DELAY,
+                proposal_threshold: PROPOSAL_THRESHOLD,
+            },
+        }
+        .data(),
+    }
+}
+
+fn initialize_escrow(mint: &Pubkey, authority: &Pubkey, payer: &Pubkey) -> Instruction {
+    let (escrow, _) = Pubkey::find_program_address(&[b"escrow", mint.as_ref()], &solearn_vote_escrow::id());
+    Instruction {
+        program_id: solearn_vote_escrow::id(),
+        accounts: solearn_vote_escrow::accounts::InitializeEscrow {
+            escrow,
+            vault: Pubkey::find_program_address(&[b"escrow-vault", escrow.as_ref()], &solearn_vote_escrow::id()).0,
+            mint: *mint,
+            mint_config: None,
+            authority: *authority,
+            payer: *payer,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_vote_escrow::instruction::InitializeEscrow {
+            min_lock_duration: MIN_LOCK_DURATION,
+            max_lock_duration: MAX_LOCK_DURATION,
+        }
+        .data(),
+    }
+}
+
+/// Start a validator with a LEARN mint, three funded voters, an initialized
+/// learning program whose admin proposed handing authority to the
+/// governance signer, and the governance itself
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_governance",
+        solearn_governance::id(),
+        processor!(solearn_governance::entry),
+    );
+    program_test.add_program(
+        "solearn_learning",
+        solearn_learning::id(),
+        processor!(solearn_learning::entry),
+    );
+    program_test.add_program(
+        "solearn_vote_escrow",
+        solearn_vote_escrow::id(),
+        processor!(solearn_vote_escrow::entry),
+    );
+
+    let admin = Keypair::new();
+    program_test.add_account(admin.pubkey(), funded_account());
+    let voter_keys: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for voter in &voter_keys {
+        program_test.add_account(voter.pubkey(), funded_account());
+    }
+
+    let context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    let mint = Keypair::new();
+    let (governance, _) = Pubkey::find_program_address(
+        &[b"governance", mint.pubkey().as_ref()],
+        &solearn_governance::id(),
+    );
+    let (governance_signer, _) = Pubkey::find_program_address(
+        &[b"governance-signer", governance.as_ref()],
+        &solearn_governance::id(),
+    );
+    let (vault, _) = Pubkey::find_program_address(
+        &[b"governance-vault", governance.as_ref()],
+        &solearn_governance::id(),
+    );
+    let (escrow, _) = Pubkey::find_program_address(
+        &[b"escrow", mint.pubkey().as_ref()],
+        &solearn_vote_escrow::id(),
+    );
+    let (escrow_vault, _) = Pubkey::find_program_address(
+        &[b"escrow-vault", escrow.as_ref()],
+        &solearn_vote_escrow::id(),
+    );
+    let tenant = Keypair::new();
+    let (program_state, _) = Pubkey::find_program_address(
+        &[b"program_state", tenant.pubkey().as_ref()],
+        &solearn_learning::id(),
+    );
+
+    let mut fixture = Fixture {
+        context,
+        admin,
+        governance,
+        governance_signer,
+        vault,
+        escrow,
+        escrow_vault,
+        program_state,
+        voters: Vec::new(),
+        now,
+    };
+    let admin = fixture.admin.insecure_clone();
+
+    // LEARN mint and voter token accounts
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &admin.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &admin.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    let token_accounts: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for ((voter, token_account), amount) in voter_keys.iter().zip(&token_accounts).zip([600, 300, 100]) {
+        instructions.extend([
+            system_instruction::create_account(
+                &admin.pubkey(),
+                &token_account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token_account.pubkey(),
+                &mint.pubkey(),
+                &voter.pubkey(),
+            )
+            .unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &mint.pubkey(),
+                &token_account.pubkey(),
+                &admin.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ]);
+    }
+    let mut signers = vec![&mint];
+    signers.extend(token_accounts.iter());
+    fixture.process(&instructions, &admin, &signers).await.unwrap();
+    fixture.voters = voter_keys
+        .into_iter()
+        .zip(token_accounts.iter().map(Keypair::pubkey))
+        .collect();
+
+    // Learning program, with its authority offered to the governance signer
+    let initialize_program = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::InitializeProgram {
+            program_state,
+            tenant: tenant.pubkey(),
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::InitializeProgram {
+            token_mint: mint.pubkey(),
+            token_program: spl_token::id(),
+        }
+        .data(),
+    };
+    let propose_authority = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::AdminAction {
+            program_state,
+            authority: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::ProposeAuthority {
+            new_authority: governance_signer,
+            delay: 0,
+        }
+        .data(),
+    };
+    let create_governance = create_governance(&mint.pubkey(), &admin.pubkey(), &admin.pubkey());
+    let initialize_escrow = initialize_escrow(&mint.pubkey(), &admin.pubkey(), &admin.pubkey());
+    fixture
+        .process(
+            &[initialize_program, propose_authority, create_governance, initialize_escrow],
+            &admin,
+            &[&tenant],
+        )
+        .await
+        .unwrap();
+
+    fixture
+}
+
+#[tokio::test]
+async fn passed_proposal_executes_after_delay() {
+    let mut fixture = setup().await;
+    fixture.deposit(0, 600).await.unwrap();
+    fixture.deposit(1, 300).await.unwrap();
+
+    // Take over the learning program
+    fixture
+        .create_proposal(0, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+    fixture.vote(0, 0, true).await.unwrap();
+    fixture.vote(1, 0, false).await.unwrap();
+
+    // Not while voting is open
+    assert!(fixture.execute(0).await.is_err());
+
+    // Not before the execution delay has elapsed
+    fixture.now += VOTING_PERIOD;
+    assert!(fixture.execute(0).await.is_err());
+
+    fixture.now += EXECUTION_DELAY;
+    fixture.execute(0).await.unwrap();
+
+    let program_state: solearn_learning::ProgramState = fixture.account(fixture.program_state).await;
+    assert_eq!(program_state.authority, fixture.governance_signer);
+
+    // A proposal cannot be executed twice
+    assert!(fixture.execute(0).await.is_err());
+
+    // Governance now controls the program's configuration
+    fixture
+        .create_proposal(
+            0,
+            1,
+            ProposalAction::UpdateProgramConfig {
+                program_state: fixture.program_state,
+                pause_program: Some(true),
+                paused_operations: None,
+            },
+        )
+        .await
+        .unwrap();
+    fixture.vote(0, 1, true).await.unwrap();
+    fixture.now += VOTING_PERIOD + EXECUTION_DELAY;
+    fixture.execute(1).await.unwrap();
+
+    let program_state: solearn_learning::ProgramState = fixture.account(fixture.program_state).await;
+    assert!(program_state.paused);
+    assert_eq!(program_state.paused_by, fixture.governance_signer);
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(1)).await;
+    assert!(proposal.executed);
+    assert_eq!(proposal.yes_votes, 600);
+}
+
+#[tokio::test]
+async fn proposal_below_quorum_or_majority_fails() {
+    let mut fixture = setup().await;
+    fixture.deposit(1, 300).await.unwrap();
+    fixture.deposit(2, 100).await.unwrap();
+
+    // 400 votes in total stay below the quorum of 500
+    fixture
+        .create_proposal(2, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+    fixture.vote(1, 0, true).await.unwrap();
+    fixture.vote(2, 0, true).await.unwrap();
+    fixture.now += VOTING_PERIOD + EXECUTION_DELAY;
+    assert!(fixture.execute(0).await.is_err());
+
+    // Quorum reached but more votes against
+    fixture.deposit(0, 600).await.unwrap();
+    fixture
+        .create_proposal(2, 1, fixture.accept_program_authority())
+        .await
+        .unwrap();
+    fixture.vote(0, 1, false).await.unwrap();
+    fixture.vote(1, 1, true).await.unwrap();
+    fixture.now += VOTING_PERIOD + EXECUTION_DELAY;
+    assert!(fixture.execute(1).await.is_err());
+
+    let program_state: solearn_learning::ProgramState = fixture.account(fixture.program_state).await;
+    assert_eq!(program_state.authority, fixture.admin.pubkey());
+}
+
+#[tokio::test]
+async fn votes_count_once_and_lock_deposits() {
+    let mut fixture = setup().await;
+    fixture.deposit(0, 600).await.unwrap();
+
+    // Proposing needs the threshold in deposited votes
+    assert!(fixture
+        .create_proposal(1, 0, fixture.accept_program_authority())
+        .await
+        .is_err());
+
+    fixture
+        .create_proposal(0, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+    fixture.vote(0, 0, true).await.unwrap();
+    assert!(fixture.vote(0, 0, true).await.is_err());
+
+    // Deposits stay locked while the voted proposal is open
+    assert!(fixture.withdraw(0, 600).await.is_err());
+
+    // Voting closes at the end of the period
+    fixture.now += VOTING_PERIOD;
+    fixture.deposit(1, 300).await.unwrap();
+    assert!(fixture.vote(1, 0, false).await.is_err());
+
+    fixture.withdraw(0, 600).await.unwrap();
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(0)).await;
+    assert_eq!(proposal.yes_votes, 600);
+    assert_eq!(proposal.no_votes, 0);
+}
+
+#[tokio::test]
+async fn delegated_votes_count_once_and_direct_votes_override() {
+    let mut fixture = setup().await;
+    fixture.deposit(0, 600).await.unwrap();
+    fixture.deposit(1, 300).await.unwrap();
+    fixture.deposit(2, 100).await.unwrap();
+    fixture.delegate(1, 2).await.unwrap();
+
+    fixture
+        .create_proposal(0, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+
+    // Only the delegate can vote with the delegated weight, and only once
+    assert!(fixture.delegated_vote(0, 1, 0, true).await.is_err());
+    fixture.vote(2, 0, true).await.unwrap();
+    fixture.delegated_vote(2, 1, 0, true).await.unwrap();
+    assert!(fixture.delegated_vote(2, 1, 0, true).await.is_err());
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(0)).await;
+    assert_eq!(proposal.yes_votes, 400);
+
+    // Redelegating mid-vote does not count the weight again
+    fixture.revoke_delegation(1).await.unwrap();
+    fixture.delegate(1, 0).await.unwrap();
+    assert!(fixture.delegated_vote(0, 1, 0, false).await.is_err());
+
+    // The delegated deposit is locked like a direct vote's
+    assert!(fixture.withdraw(1, 300).await.is_err());
+
+    // A direct vote replaces the delegate's vote, once
+    fixture.vote(1, 0, false).await.unwrap();
+    assert!(fixture.vote(1, 0, true).await.is_err());
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(0)).await;
+    assert_eq!(proposal.yes_votes, 100);
+    assert_eq!(proposal.no_votes, 300);
+
+    // Nor can a delegate vote for someone who already voted directly
+    fixture.vote(0, 0, true).await.unwrap();
+    fixture.delegate(0, 2).await.unwrap();
+    assert!(fixture.delegated_vote(2, 0, 0, false).await.is_err());
+}
+
+#[tokio::test]
+async fn escrow_tokens_cannot_vote_twice() {
+    let mut fixture = setup().await;
+    fixture.deposit(0, 600).await.unwrap();
+
+    // One lock outlasts the vote, the other expires halfway through it
+    let start = fixture.now;
+    fixture.create_lock(1, 300, start + VOTING_PERIOD / 2).await.unwrap();
+    fixture.create_lock(2, 100, start + MAX_LOCK_DURATION).await.unwrap();
+    fixture
+        .create_proposal(0, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+
+    // A lock that can be withdrawn before voting ends does not count
+    assert!(fixture.vote_with_lock(1, 0, true).await.is_err());
+    fixture.vote_with_lock(2, 0, true).await.unwrap();
+    assert!(fixture.vote_with_lock(2, 0, true).await.is_err());
+
+    // Tokens withdrawn mid-vote and relocked in another wallet add nothing
+    fixture.now = start + VOTING_PERIOD / 2;
+    fixture.withdraw_lock(1).await.unwrap();
+    let sender = fixture.voters[1].0.insecure_clone();
+    let transfer = spl_token::instruction::transfer(
+        &spl_token::id(),
+        &fixture.voters[1].1,
+        &fixture.voters[0].1,
+        &sender.pubkey(),
+        &[],
+        300,
+    )
+    .unwrap();
+    fixture.process(&[transfer], &sender, &[]).await.unwrap();
+    fixture
+        .create_lock(0, 300, fixture.now + MAX_LOCK_DURATION)
+        .await
+        .unwrap();
+    fixture.vote_with_lock(0, 0, true).await.unwrap();
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(0)).await;
+    assert_eq!(proposal.yes_votes, 600 + 100);
+    assert_eq!(proposal.no_votes, 0);
+}
+
+#[tokio::test]
+async fn only_the_mint_authority_creates_a_governance() {
+    let mut fixture = setup().await;
+    let admin = fixture.admin.insecure_clone();
+    let (voter, _) = &fixture.voters[0];
+    let voter = voter.insecure_clone();
+    let mint = fixture.create_mint().await;
+
+    // A holder cannot claim the mint's governance with their own config
+    let squat = create_governance(&mint, &voter.pubkey(), &voter.pubkey());
+    assert!(fixture.process(&[squat], &voter, &[]).await.is_err());
+
+    // The mint authority can, even when someone else pays for it
+    let create = create_governance(&mint, &admin.pubkey(), &voter.pubkey());
+    fixture.process(&[create], &voter, &[&admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn only_the_mint_authority_creates_an_escrow() {
+    let mut fixture = setup().await;
+    let admin = fixture.admin.insecure_clone();
+    let (voter, _) = &fixture.voters[0];
+    let voter = voter.insecure_clone();
+    let mint = fixture.create_mint().await;
+
+    // A holder cannot claim the mint's escrow with their own lock durations
+    let squat = initialize_escrow(&mint, &voter.pubkey(), &voter.pubkey());
+    assert!(fixture.process(&[squat], &voter, &[]).await.is_err());
+
+    let initialize = initialize_escrow(&mint, &admin.pubkey(), &voter.pubkey());
+    fixture.process(&[initialize], &voter, &[&admin]).await.unwrap();
+}
+
+#[tokio::test]
+async fn lock_changes_during_voting_add_no_power() {
+    let mut fixture = setup().await;
+    fixture.deposit(0, 600).await.unwrap();
+    let start = fixture.now;
+    fixture.create_lock(1, 200, start + MAX_LOCK_DURATION).await.unwrap();
+    fixture.create_lock(2, 50, start + MAX_LOCK_DURATION).await.unwrap();
+    fixture
+        .create_proposal(0, 0, fixture.accept_program_authority())
+        .await
+        .unwrap();
+
+    // Topping up a lock after voting started drops it from the snapshot
+    fixture.now = start + 10;
+    fixture.increase_lock_amount(1, 100).await.unwrap();
+    assert!(fixture.vote_with_lock(1, 0, true).await.is_err());
+
+    // So does pushing its unlock time out
+    fixture.extend_lock(2, start + 10 + MAX_LOCK_DURATION).await.unwrap();
+    assert!(fixture.vote_with_lock(2, 0, true).await.is_err());
+
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(0)).await;
+    assert_eq!(proposal.yes_votes, 0);
+
+    // The changed locks count in full for proposals created afterwards
+    fixture
+        .create_proposal(0, 1, fixture.accept_program_authority())
+        .await
+        .unwrap();
+    fixture.vote_with_lock(1, 1, true).await.unwrap();
+    let proposal: solearn_governance::Proposal = fixture.account(fixture.proposal(1)).await;
+    assert_eq!(proposal.yes_votes, 300 * (MAX_LOCK_DURATION - 10) as u64 / MAX_LOCK_DURATION as u64);
+}
@@ -1,34 +1,224 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo};
-use solana_program::program::invoke_signed;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, CloseAccount, Mint, Token, TokenAccount, Transfer};
+use solana_program::ed25519_program;
+use solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked};
+use solearn_staking::{StakePosition, StakingPool};
+use solearn_token::program::SolearnToken;
 use std::convert::TryFrom;
 
-declare_id!("LearnAchievementProgram1111111111111111111111");
+declare_id!("LearnAchievementProgram11111111111111111111");
 
 #[program]
 pub mod solearn_learning {
     use super::*;
 
-    /// Initialize the global learning program state
+    /// Initialize the program state of a tenant, a platform instance with
+    /// its own reward mint, roles and pause state. The tenant key signs so
+    /// nobody else can claim it. Every other learning account is seeded by
+    /// its tenant's program state.
     pub fn initialize_program(
         ctx: Context<InitializeProgram>,
         token_mint: Pubkey,
         token_program: Pubkey,
     ) -> Result<()> {
+        let tenant = ctx.accounts.tenant.key();
         let program_state = &mut ctx.accounts.program_state;
+        program_state.tenant = tenant;
         program_state.authority = ctx.accounts.authority.key();
         program_state.token_mint = token_mint;
         program_state.token_program = token_program;
         program_state.total_courses = 0;
         program_state.total_learners = 0;
         program_state.paused = false;
+        program_state.paused_operations = 0;
+        program_state.paused_by = Pubkey::default();
+        program_state.paused_at = 0;
+        program_state.pause_reason = PauseReason::default();
+        program_state.pausers = Vec::new();
+        program_state.course_registrars = Vec::new();
+        program_state.assessors = Vec::new();
+        program_state.treasurers = Vec::new();
+        program_state.reviewers = Vec::new();
+        program_state.course_bond = 0;
+        program_state.review_quorum = 1;
+        program_state.rejection_penalty_bps = 0;
+        program_state.bond_treasury = Pubkey::default();
+        program_state.staking_pool = Pubkey::default();
+        program_state.pending_authority = Pubkey::default();
+        program_state.authority_transfer_eta = 0;
+        program_state.bump = *ctx.bumps.get("program_state").unwrap();
         
-        msg!("Learning program initialized with token mint: {}", token_mint);
+        msg!("Learning program initialized for tenant {} with token mint: {}", tenant, token_mint);
         
         Ok(())
     }
 
-    /// Register a new course in the system
+    /// Move a keypair-based program state from an earlier deployment to the
+    /// program state PDA of `tenant` and close the old account
+    pub fn migrate_program_state(ctx: Context<MigrateProgramState>) -> Result<()> {
+        let legacy_info = ctx.accounts.legacy_program_state.to_account_info();
+
+        let legacy = {
+            let data = legacy_info.try_borrow_data()?;
+            if data.len() != 8 + LegacyProgramState::LEN || data[..8] != ProgramState::discriminator() {
+                return Err(ErrorCode::InvalidLegacyProgramState.into());
+            }
+            // Tenant program states share the discriminator and start with
+            // their tenant key; never take one for a legacy account
+            let tenant_key = Pubkey::try_from(&data[8..40]).unwrap();
+            let (live_state, _) =
+                Pubkey::find_program_address(&[b"program_state", tenant_key.as_ref()], &crate::ID);
+            if live_state == legacy_info.key() {
+                return Err(ErrorCode::InvalidLegacyProgramState.into());
+            }
+            LegacyProgramState::deserialize(&mut &data[8..])?
+        };
+        if legacy.authority != ctx.accounts.authority.key() {
+            return Err(ErrorCode::Unauthorized.into());
+        }
+
+        let program_state = &mut ctx.accounts.program_state;
+        program_state.tenant = ctx.accounts.tenant.key();
+        program_state.authority = legacy.authority;
+        program_state.token_mint = legacy.token_mint;
+        program_state.token_program = legacy.token_program;
+        program_state.total_courses = legacy.total_courses;
+        program_state.total_learners = legacy.total_learners;
+        program_state.paused = legacy.paused;
+        program_state.paused_operations = 0;
+        program_state.paused_by = if legacy.paused { legacy.authority } else { Pubkey::default() };
+        program_state.paused_at = 0;
+        program_state.pause_reason = PauseReason::default();
+        program_state.pausers = Vec::new();
+        program_state.course_registrars = Vec::new();
+        program_state.assessors = Vec::new();
+        program_state.treasurers = Vec::new();
+        program_state.reviewers = Vec::new();
+        program_state.course_bond = 0;
+        program_state.review_quorum = 1;
+        program_state.rejection_penalty_bps = 0;
+        program_state.bond_treasury = Pubkey::default();
+        program_state.staking_pool = Pubkey::default();
+        program_state.pending_authority = Pubkey::default();
+        program_state.authority_transfer_eta = 0;
+        program_state.bump = *ctx.bumps.get("program_state").unwrap();
+
+        // Close the legacy account and refund its rent to the authority
+        let authority_info = ctx.accounts.authority.to_account_info();
+        **authority_info.lamports.borrow_mut() = authority_info
+            .lamports()
+            .checked_add(legacy_info.lamports())
+            .unwrap();
+        **legacy_info.lamports.borrow_mut() = 0;
+        legacy_info.try_borrow_mut_data()?.fill(0);
+
+        msg!(
+            "Program state migrated from {} to {}",
+            legacy_info.key(),
+            program_state.key()
+        );
+
+        Ok(())
+    }
+
+    /// Create an institution, the namespace its courses' IDs live in. The
+    /// caller becomes its authority.
+    pub fn create_institution(
+        ctx: Context<CreateInstitution>,
+        institution_id: String,
+        name: String,
+        metadata_uri: String,
+    ) -> Result<()> {
+        if institution_id.len() > Institution::MAX_ID_LEN {
+            return Err(ErrorCode::InstitutionIdTooLong.into());
+        }
+        if name.len() > Institution::MAX_NAME_LEN || metadata_uri.len() > Institution::MAX_URI_LEN {
+            return Err(ErrorCode::InstitutionMetadataTooLong.into());
+        }
+        ctx.accounts.program_state.require_active(operation::REGISTRATION)?;
+
+        let institution = &mut ctx.accounts.institution;
+        institution.program_state = ctx.accounts.program_state.key();
+        institution.authority = ctx.accounts.authority.key();
+        institution.institution_id = institution_id;
+        institution.name = name;
+        institution.metadata_uri = metadata_uri;
+        institution.managers = Vec::new();
+        institution.total_courses = 0;
+        institution.total_completions = 0;
+        institution.total_rewards = 0;
+        institution.created_at = Clock::get()?.unix_timestamp;
+        institution.bump = *ctx.bumps.get("institution").unwrap();
+
+        msg!("Institution created: {}", institution.name);
+
+        Ok(())
+    }
+
+    /// Update an institution's name and metadata URI
+    pub fn update_institution(
+        ctx: Context<InstitutionAdmin>,
+        name: Option<String>,
+        metadata_uri: Option<String>,
+    ) -> Result<()> {
+        let institution = &mut ctx.accounts.institution;
+
+        if let Some(name) = name {
+            if name.len() > Institution::MAX_NAME_LEN {
+                return Err(ErrorCode::InstitutionMetadataTooLong.into());
+            }
+            institution.name = name;
+        }
+
+        if let Some(metadata_uri) = metadata_uri {
+            if metadata_uri.len() > Institution::MAX_URI_LEN {
+                return Err(ErrorCode::InstitutionMetadataTooLong.into());
+            }
+            institution.metadata_uri = metadata_uri;
+        }
+
+        msg!("Institution updated: {}", institution.name);
+
+        Ok(())
+    }
+
+    /// Let `manager` register, submit and update courses for the institution
+    pub fn add_institution_manager(ctx: Context<InstitutionAdmin>, manager: Pubkey) -> Result<()> {
+        let institution = &mut ctx.accounts.institution;
+
+        if institution.managers.contains(&manager) {
+            return Err(ErrorCode::RoleAlreadyGranted.into());
+        }
+        if institution.managers.len() >= Institution::MAX_MANAGERS {
+            return Err(ErrorCode::TooManyRoleMembers.into());
+        }
+        institution.managers.push(manager);
+
+        msg!("Added manager {} to institution {}", manager, institution.name);
+
+        Ok(())
+    }
+
+    /// Remove a course manager from the institution
+    pub fn remove_institution_manager(ctx: Context<InstitutionAdmin>, manager: Pubkey) -> Result<()> {
+        let institution = &mut ctx.accounts.institution;
+
+        let index = institution
+            .managers
+            .iter()
+            .position(|member| *member == manager)
+            .ok_or(ErrorCode::RoleNotGranted)?;
+        institution.managers.swap_remove(index);
+
+        msg!("Removed manager {} from institution {}", manager, institution.name);
+
+        Ok(())
+    }
+
+    /// Register a new course in the system. Requires the course registrar
+    /// role; the course skips review and is approved immediately. `content`
+    /// is published as version 1.
     pub fn register_course(
         ctx: Context<RegisterCourse>,
         course_id: String,
@@ -36,24 +226,17 @@ pub mod solearn_learning {
         description: String,
         reward_amount: u64,
         required_score: u8,
+        content: CourseContent,
     ) -> Result<()> {
-        // Validate inputs
-        if course_id.len() > 20 {
-            return Err(ErrorCode::CourseTitleTooLong.into());
-        }
-        if title.len() > 100 {
-            return Err(ErrorCode::CourseTitleTooLong.into());
-        }
-        if description.len() > 200 {
-            return Err(ErrorCode::CourseDescriptionTooLong.into());
-        }
-        if required_score > 100 {
-            return Err(ErrorCode::InvalidRequiredScore.into());
-        }
+        validate_course(&course_id, &title, &description, required_score)?;
+        ctx.accounts.program_state.require_active(operation::REGISTRATION)?;
+        let now = Clock::get()?.unix_timestamp;
 
         // Initialize course data
         let course = &mut ctx.accounts.course;
         course.authority = ctx.accounts.authority.key();
+        course.program_state = ctx.accounts.program_state.key();
+        course.institution = ctx.accounts.institution.key();
         course.course_id = course_id;
         course.title = title;
         course.description = description;
@@ -61,17 +244,221 @@ pub mod solearn_learning {
         course.required_score = required_score;
         course.active = true;
         course.completed_count = 0;
-        course.created_at = Clock::get()?.unix_timestamp;
+        course.created_at = now;
+        course.status = CourseStatus::Approved;
+        course.bond_amount = 0;
+        course.approvals = 0;
+        course.rejections = 0;
+        course.reviewed_at = now;
+        course.version = 0;
+        course.accept_previous_versions = true;
+        course.review_round = 0;
+        publish_version(
+            course,
+            &mut ctx.accounts.course_version,
+            content,
+            *ctx.bumps.get("course_version").unwrap(),
+            now,
+        )?;
 
-        // Update program state
+        // Update program and institution stats
         let program_state = &mut ctx.accounts.program_state;
         program_state.total_courses += 1;
+        ctx.accounts.institution.total_courses += 1;
 
         msg!("Course registered: {}", course.title);
         
         Ok(())
     }
 
+    /// Submit a course for review. Any educator can submit; the course stays
+    /// `Pending` until reviewers approve or reject it, and the configured
+    /// LEARN bond is held in the course's bond escrow until then.
+    pub fn submit_course(
+        ctx: Context<SubmitCourse>,
+        course_id: String,
+        title: String,
+        description: String,
+        reward_amount: u64,
+        required_score: u8,
+        content: CourseContent,
+    ) -> Result<()> {
+        validate_course(&course_id, &title, &description, required_score)?;
+        let program_state = &ctx.accounts.program_state;
+        program_state.require_active(operation::REGISTRATION)?;
+
+        let bond_amount = program_state.course_bond;
+        if bond_amount > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.educator_token_account.to_account_info(),
+                to: ctx.accounts.bond_escrow.to_account_info(),
+                authority: ctx.accounts.educator.to_account_info(),
+            };
+            let cpi_ctx = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+            token::transfer(cpi_ctx, bond_amount)?;
+        }
+
+        let course = &mut ctx.accounts.course;
+        course.authority = ctx.accounts.educator.key();
+        course.program_state = ctx.accounts.program_state.key();
+        course.institution = ctx.accounts.institution.key();
+        course.course_id = course_id;
+        course.title = title;
+        course.description = description;
+        course.reward_amount = reward_amount;
+        course.required_score = required_score;
+        course.active = true;
+        course.completed_count = 0;
+        course.created_at = Clock::get()?.unix_timestamp;
+        course.status = CourseStatus::Pending;
+        course.bond_amount = bond_amount;
+        course.approvals = 0;
+        course.rejections = 0;
+        course.reviewed_at = 0;
+        course.version = 0;
+        course.accept_previous_versions = true;
+        course.review_round = 0;
+        publish_version(
+            course,
+            &mut ctx.accounts.course_version,
+            content,
+            *ctx.bumps.get("course_version").unwrap(),
+            course.created_at,
+        )?;
+
+        emit!(CourseSubmitted {
+            course: course.key(),
+            educator: course.authority,
+            bond_amount,
+        });
+
+        msg!("Course submitted for review: {}", course.title);
+
+        Ok(())
+    }
+
+    /// Vote to approve or reject a pending course. Requires the reviewer
+    /// role. The course is decided once either side reaches the review
+    /// quorum.
+    pub fn review_course(ctx: Context<ReviewCourse>, approve: bool) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+        program_state.require_active(operation::CURATION)?;
+        let course = &mut ctx.accounts.course;
+        if course.status != CourseStatus::Pending {
+            return Err(ErrorCode::CourseNotPending.into());
+        }
+
+        let review = &mut ctx.accounts.course_review;
+        review.course = course.key();
+        review.reviewer = ctx.accounts.reviewer.key();
+        review.approve = approve;
+        review.reviewed_at = Clock::get()?.unix_timestamp;
+
+        if approve {
+            course.approvals = course.approvals.saturating_add(1);
+        } else {
+            course.rejections = course.rejections.saturating_add(1);
+        }
+
+        let quorum = program_state.review_quorum.max(1);
+        if course.approvals >= quorum {
+            course.status = CourseStatus::Approved;
+            program_state.total_courses += 1;
+            ctx.accounts.institution.total_courses += 1;
+        } else if course.rejections >= quorum {
+            course.status = CourseStatus::Rejected;
+        }
+        if course.status != CourseStatus::Pending {
+            course.reviewed_at = review.reviewed_at;
+            emit!(CourseReviewed {
+                course: course.key(),
+                status: course.status,
+                approvals: course.approvals,
+                rejections: course.rejections,
+            });
+        }
+
+        msg!(
+            "Course {} reviewed by {}: {}",
+            course.title,
+            review.reviewer,
+            if approve { "approve" } else { "reject" }
+        );
+
+        Ok(())
+    }
+
+    /// Return the bond of a reviewed course to its educator. A rejected
+    /// course forfeits `rejection_penalty_bps` of its bond to the bond
+    /// treasury.
+    pub fn reclaim_course_bond(ctx: Context<ReclaimCourseBond>) -> Result<()> {
+        let program_state = &ctx.accounts.program_state;
+        program_state.require_active(operation::CURATION)?;
+        let course = &mut ctx.accounts.course;
+        if course.status == CourseStatus::Pending {
+            return Err(ErrorCode::CourseNotReviewed.into());
+        }
+
+        let bond_amount = course.bond_amount;
+        let forfeited = if course.status == CourseStatus::Rejected {
+            let penalty = u128::from(bond_amount) * u128::from(program_state.rejection_penalty_bps) / 10_000;
+            u64::try_from(penalty).unwrap()
+        } else {
+            0
+        };
+        let refunded = bond_amount - forfeited;
+        course.bond_amount = 0;
+
+        let seeds = &[b"program_state".as_ref(), program_state.tenant.as_ref(), &[program_state.bump]];
+        let signer = &[&seeds[..]];
+        let token_program = ctx.accounts.token_program.to_account_info();
+
+        if forfeited > 0 {
+            let bond_treasury = ctx
+                .accounts
+                .bond_treasury
+                .as_ref()
+                .ok_or(ErrorCode::InvalidBondTreasury)?;
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.bond_escrow.to_account_info(),
+                to: bond_treasury.to_account_info(),
+                authority: program_state.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer),
+                forfeited,
+            )?;
+        }
+        if refunded > 0 {
+            let cpi_accounts = Transfer {
+                from: ctx.accounts.bond_escrow.to_account_info(),
+                to: ctx.accounts.educator_token_account.to_account_info(),
+                authority: program_state.to_account_info(),
+            };
+            token::transfer(
+                CpiContext::new_with_signer(token_program.clone(), cpi_accounts, signer),
+                refunded,
+            )?;
+        }
+
+        // Close the escrow and return its rent to the educator
+        let cpi_accounts = CloseAccount {
+            account: ctx.accounts.bond_escrow.to_account_info(),
+            destination: ctx.accounts.educator.to_account_info(),
+            authority: program_state.to_account_info(),
+        };
+        token::close_account(CpiContext::new_with_signer(token_program, cpi_accounts, signer))?;
+
+        msg!(
+            "Course bond reclaimed for {}: {} refunded, {} forfeited",
+            course.title,
+            refunded,
+            forfeited
+        );
+
+        Ok(())
+    }
+
     /// Register a new learner in the system
     pub fn register_learner(
         ctx: Context<RegisterLearner>,
@@ -80,6 +467,7 @@ pub mod solearn_learning {
         if learner_name.len() > 50 {
             return Err(ErrorCode::LearnerNameTooLong.into());
         }
+        ctx.accounts.program_state.require_active(operation::REGISTRATION)?;
 
         let learner = &mut ctx.accounts.learner;
         learner.wallet = ctx.accounts.wallet.key();
@@ -98,23 +486,79 @@ pub mod solearn_learning {
         Ok(())
     }
 
-    /// Record completion of a course by a learner and distribute rewards
+    /// Grant `role` to `key`. The admin role is the program authority and
+    /// changes hands through `propose_authority` instead.
+    pub fn grant_role(ctx: Context<AdminAction>, role: Role, key: Pubkey) -> Result<()> {
+        let members = ctx
+            .accounts
+            .program_state
+            .role_members_mut(role)
+            .ok_or(ErrorCode::InvalidRole)?;
+
+        if members.contains(&key) {
+            return Err(ErrorCode::RoleAlreadyGranted.into());
+        }
+        if members.len() >= ProgramState::MAX_ROLE_MEMBERS {
+            return Err(ErrorCode::TooManyRoleMembers.into());
+        }
+
+        members.push(key);
+
+        msg!("Granted {:?} role to: {}", role, key);
+
+        Ok(())
+    }
+
+    /// Revoke `role` from `key`
+    pub fn revoke_role(ctx: Context<AdminAction>, role: Role, key: Pubkey) -> Result<()> {
+        let members = ctx
+            .accounts
+            .program_state
+            .role_members_mut(role)
+            .ok_or(ErrorCode::InvalidRole)?;
+
+        let index = members
+            .iter()
+            .position(|member| *member == key)
+            .ok_or(ErrorCode::RoleNotGranted)?;
+        members.swap_remove(index);
+
+        msg!("Revoked {:?} role from: {}", role, key);
+
+        Ok(())
+    }
+
+    /// Record completion of a course by a learner and distribute rewards.
+    /// The transaction must carry an ed25519 signature from a registered
+    /// assessor over the `CompletionAttestation` in the instruction
+    /// immediately preceding this one. `course_version` is the version the
+    /// learner studied; older versions only count while the course accepts
+    /// them.
     pub fn complete_course(
         ctx: Context<CompleteCourse>,
         score: u8,
         evidence_hash: String,
+        nonce: u64,
+        course_version: u32,
     ) -> Result<()> {
-        // Check program is not paused
+        // Check completions are not paused
         let program_state = &mut ctx.accounts.program_state;
-        if program_state.paused {
-            return Err(ErrorCode::ProgramPaused.into());
-        }
+        program_state.require_active(operation::COMPLETION)?;
 
-        // Check course is active
+        // Check course is approved and active
         let course = &mut ctx.accounts.course;
+        if course.status != CourseStatus::Approved {
+            return Err(ErrorCode::CourseNotApproved.into());
+        }
         if !course.active {
             return Err(ErrorCode::CourseInactive.into());
         }
+        if !course.accepts_version(course_version) {
+            return Err(ErrorCode::CourseVersionNotAccepted.into());
+        }
+        if evidence_hash.len() > CourseCompletion::MAX_EVIDENCE_HASH_LEN {
+            return Err(ErrorCode::EvidenceHashTooLong.into());
+        }
 
         // Check learner achieved required score
         if score < course.required_score {
@@ -127,27 +571,58 @@ pub mod solearn_learning {
             return Err(ErrorCode::CourseAlreadyCompleted.into());
         }
 
+        // Check the score and evidence were attested by a registered assessor
+        let attestation = CompletionAttestation {
+            learner: ctx.accounts.wallet.key(),
+            course: course.key(),
+            course_version,
+            score,
+            evidence_hash: evidence_hash.clone(),
+            nonce,
+        };
+        let assessor = verify_assessor_attestation(
+            &ctx.accounts.instructions_sysvar,
+            program_state,
+            &attestation,
+        )?;
+
         // Calculate XP based on score (higher score = more XP)
         let xp_earned = u64::from(score).saturating_mul(10); // 10 XP per point
 
+        // Apply the learner's staking tier boost to the token reward
+        let tokens_earned = boosted_reward(
+            course.reward_amount,
+            &ctx.accounts.wallet.key(),
+            &program_state.staking_pool,
+            &ctx.accounts.staking_pool,
+            &ctx.accounts.stake_position,
+        )?;
+
         // Update learner stats
         let learner = &mut ctx.accounts.learner;
         learner.total_xp = learner.total_xp.saturating_add(xp_earned);
         learner.courses_completed = learner.courses_completed.saturating_add(1);
         learner.last_activity = Clock::get()?.unix_timestamp;
 
-        // Update course stats
+        // Update course and institution stats
         course.completed_count = course.completed_count.saturating_add(1);
+        let institution = &mut ctx.accounts.institution;
+        institution.total_completions = institution.total_completions.saturating_add(1);
+        institution.total_rewards = institution.total_rewards.saturating_add(tokens_earned);
 
         // Record completion
         completion.learner = learner.wallet;
         completion.course = course.key();
+        completion.course_version = course_version;
         completion.completed = true;
         completion.score = score;
         completion.evidence_hash = evidence_hash;
         completion.completed_at = Clock::get()?.unix_timestamp;
         completion.xp_earned = xp_earned;
-        completion.tokens_earned = course.reward_amount;
+        completion.tokens_earned = tokens_earned;
+        completion.assessor = assessor;
+        completion.attestation_nonce = nonce;
+        completion.reward_minted = false;
 
         msg!(
             "Course completed: {} by {}. Score: {}, XP earned: {}",
@@ -157,40 +632,87 @@ pub mod solearn_learning {
             xp_earned
         );
 
-        // Call the token program to mint reward tokens to the learner
-        // Transfer tokens from the program's token account to the learner's wallet
-        let token_program = ctx.accounts.program_state.token_program;
-        let token_mint = ctx.accounts.program_state.token_mint;
-        
-        // Create the token accounts if they don't exist
-        let seeds = &[
-            b"program_authority",
-            &[ctx.bumps.program_state],
-        ];
-        let signer = &[&seeds[..]];
-        
-        // Mint tokens to the learner's wallet
-        let cpi_accounts = MintTo {
+        // While reward minting is paused the completion is still recorded and
+        // its reward is claimed later with `claim_completion_reward`
+        if ctx.accounts.program_state.paused_operations & operation::REWARD_MINTING != 0 {
+            msg!("Reward minting is paused, {} tokens left to claim", tokens_earned);
+            return Ok(());
+        }
+
+        let cpi_accounts = solearn_token::cpi::accounts::MintTokens {
+            mint_config: ctx.accounts.mint_config.to_account_info(),
             mint: ctx.accounts.token_mint.to_account_info(),
-            to: ctx.accounts.learner_token_account.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            token_account: ctx.accounts.learner_token_account.to_account_info(),
+            recipient: ctx.accounts.wallet.to_account_info(),
+            minter_allowance: ctx.accounts.minter_allowance.to_account_info(),
+            allocation_bucket: ctx.accounts.allocation_bucket.to_account_info(),
+            emission_schedule: Some(ctx.accounts.emission_schedule.to_account_info()),
             authority: ctx.accounts.program_state.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
         };
-        
-        let cpi_program = ctx.accounts.token_program.to_account_info();
-        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
-        
-        token::mint_to(cpi_ctx, course.reward_amount)?;
-        
-        msg!(
-            "Minted {} tokens to learner {}",
-            course.reward_amount,
-            learner.wallet
-        );
+        mint_reward(
+            &ctx.accounts.program_state,
+            ctx.accounts.solearn_token_program.to_account_info(),
+            cpi_accounts,
+            tokens_earned,
+            learner.wallet,
+        )?;
+        completion.reward_minted = true;
         
         Ok(())
     }
 
-    /// Update course details
+    /// Mint the reward of a completion recorded while reward minting was
+    /// paused
+    pub fn claim_completion_reward(ctx: Context<ClaimCompletionReward>) -> Result<()> {
+        let program_state = &ctx.accounts.program_state;
+        program_state.require_active(operation::COMPLETION)?;
+        program_state.require_active(operation::REWARD_MINTING)?;
+
+        // The course may have been sent back to review or deactivated since
+        // the completion was recorded
+        let course = &ctx.accounts.course;
+        if course.status != CourseStatus::Approved {
+            return Err(ErrorCode::CourseNotApproved.into());
+        }
+        if !course.active {
+            return Err(ErrorCode::CourseInactive.into());
+        }
+
+        let completion = &mut ctx.accounts.course_completion;
+        if completion.reward_minted {
+            return Err(ErrorCode::RewardAlreadyMinted.into());
+        }
+        if !course.accepts_version(completion.course_version) {
+            return Err(ErrorCode::CourseVersionNotAccepted.into());
+        }
+        completion.reward_minted = true;
+
+        let cpi_accounts = solearn_token::cpi::accounts::MintTokens {
+            mint_config: ctx.accounts.mint_config.to_account_info(),
+            mint: ctx.accounts.token_mint.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            token_account: ctx.accounts.learner_token_account.to_account_info(),
+            recipient: ctx.accounts.wallet.to_account_info(),
+            minter_allowance: ctx.accounts.minter_allowance.to_account_info(),
+            allocation_bucket: ctx.accounts.allocation_bucket.to_account_info(),
+            emission_schedule: Some(ctx.accounts.emission_schedule.to_account_info()),
+            authority: ctx.accounts.program_state.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+        };
+        mint_reward(
+            &ctx.accounts.program_state,
+            ctx.accounts.solearn_token_program.to_account_info(),
+            cpi_accounts,
+            completion.tokens_earned,
+            completion.learner,
+        )
+    }
+
+    /// Update course details. New `content` is published as the next version
+    /// and needs the `course_version` account for it. Changing the reward,
+    /// required score or content sends the course back to review.
     pub fn update_course(
         ctx: Context<UpdateCourse>,
         title: Option<String>,
@@ -198,8 +720,17 @@ pub mod solearn_learning {
         reward_amount: Option<u64>,
         required_score: Option<u8>,
         active: Option<bool>,
+        content: Option<CourseContent>,
+        accept_previous_versions: Option<bool>,
     ) -> Result<()> {
+        ctx.accounts.program_state.require_active(operation::COURSE_UPDATES)?;
+        // An unused version account would be created empty at the next
+        // version's address and block it
+        if ctx.accounts.course_version.is_some() != content.is_some() {
+            return Err(ErrorCode::MissingCourseVersion.into());
+        }
         let course = &mut ctx.accounts.course;
+        let mut terms_changed = false;
 
         // Update course title if provided
         if let Some(new_title) = title {
@@ -219,6 +750,7 @@ pub mod solearn_learning {
 
         // Update reward amount if provided
         if let Some(new_reward) = reward_amount {
+            terms_changed |= new_reward != course.reward_amount;
             course.reward_amount = new_reward;
         }
 
@@ -227,6 +759,7 @@ pub mod solearn_learning {
             if new_score > 100 {
                 return Err(ErrorCode::InvalidRequiredScore.into());
             }
+            terms_changed |= new_score != course.required_score;
             course.required_score = new_score;
         }
 
@@ -235,6 +768,42 @@ pub mod solearn_learning {
             course.active = new_active;
         }
 
+        // Update the old version policy if provided
+        if let Some(accept) = accept_previous_versions {
+            course.accept_previous_versions = accept;
+        }
+
+        // Publish a new content version if provided
+        if let Some(content) = content {
+            let course_version = ctx
+                .accounts
+                .course_version
+                .as_mut()
+                .ok_or(ErrorCode::MissingCourseVersion)?;
+            publish_version(
+                course,
+                course_version,
+                content,
+                *ctx.bumps.get("course_version").unwrap(),
+                Clock::get()?.unix_timestamp,
+            )?;
+            terms_changed = true;
+        }
+
+        // Reviewers approved the previous terms, so start a new review round
+        if terms_changed {
+            if course.status == CourseStatus::Approved {
+                ctx.accounts.program_state.total_courses -= 1;
+                ctx.accounts.institution.total_courses -= 1;
+            }
+            course.status = CourseStatus::Pending;
+            course.approvals = 0;
+            course.rejections = 0;
+            course.reviewed_at = 0;
+            course.review_round += 1;
+            msg!("Course terms changed, review round {} started", course.review_round);
+        }
+
         msg!("Course updated: {}", course.title);
         
         Ok(())
@@ -243,115 +812,664 @@ pub mod solearn_learning {
     /// Update program configuration
     pub fn update_program_config(
         ctx: Context<UpdateProgramConfig>,
-        new_authority: Option<Pubkey>,
         pause_program: Option<bool>,
+        paused_operations: Option<u8>,
     ) -> Result<()> {
         let program_state = &mut ctx.accounts.program_state;
+        let authority = ctx.accounts.authority.key();
+        let now = Clock::get()?.unix_timestamp;
 
-        // Update authority if provided
-        if let Some(authority) = new_authority {
-            program_state.authority = authority;
-            msg!("Updated program authority to: {}", authority);
+        // Replace the paused operation flags if provided
+        if let Some(operations) = paused_operations {
+            if operations & !operation::ALL != 0 {
+                return Err(ErrorCode::InvalidOperations.into());
+            }
+            program_state.paused_operations = operations;
+            msg!("Paused operations set to: {:#07b}", operations);
         }
 
         // Update pause state if provided
         if let Some(pause) = pause_program {
-            program_state.paused = pause;
+            if pause {
+                program_state.record_pause(authority, PauseReason::Administrative, now);
+                emit!(ProgramPaused {
+                    program_state: program_state.key(),
+                    paused_by: authority,
+                    reason: PauseReason::Administrative,
+                    paused_at: now,
+                });
+            } else {
+                program_state.record_unpause();
+                emit!(ProgramUnpaused {
+                    program_state: program_state.key(),
+                    authority,
+                });
+            }
             msg!("Program is now {}", if pause { "paused" } else { "active" });
         }
 
         Ok(())
     }
-}
 
-#[derive(Accounts)]
-pub struct InitializeProgram<'info> {
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + ProgramState::LEN
-    )]
-    pub program_state: Account<'info, ProgramState>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-    pub rent: Sysvar<'info, Rent>,
-}
+    /// Update the number of matching reviews that decide a submission
+    pub fn update_curation_config(ctx: Context<AdminAction>, review_quorum: u8) -> Result<()> {
+        if review_quorum == 0 {
+            return Err(ErrorCode::InvalidCurationConfig.into());
+        }
+        ctx.accounts.program_state.review_quorum = review_quorum;
 
-#[derive(Accounts)]
-pub struct RegisterCourse<'info> {
-    #[account(
-        mut,
-        has_one = authority,
-    )]
-    pub program_state: Account<'info, ProgramState>,
-    
-    #[account(
-        init,
-        payer = authority,
-        space = 8 + Course::LEN,
-        seeds = [b"course", course_id.as_bytes()],
-        bump,
-    )]
-    pub course: Account<'info, Course>,
-    
-    #[account(mut)]
-    pub authority: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        msg!("Review quorum set to: {}", review_quorum);
 
-#[derive(Accounts)]
-pub struct RegisterLearner<'info> {
-    #[account(mut)]
-    pub program_state: Account<'info, ProgramState>,
-    
-    #[account(
-        init,
-        payer = wallet,
-        space = 8 + Learner::LEN,
-        seeds = [b"learner", wallet.key().as_ref()],
-        bump,
-    )]
-    pub learner: Account<'info, Learner>,
-    
-    #[account(mut)]
-    pub wallet: Signer<'info>,
-    
-    pub system_program: Program<'info, System>,
-}
+        Ok(())
+    }
 
-#[derive(Accounts)]
-pub struct CompleteCourse<'info> {
-    #[account(mut)]
-    pub program_state: Account<'info, ProgramState>,
-    
-    #[account(
-        mut,
-        constraint = course.active @ ErrorCode::CourseInactive,
-    )]
-    pub course: Account<'info, Course>,
-    
-    #[account(
-        mut,
-        seeds = [b"learner", wallet.key().as_ref()],
-        bump,
-        constraint = learner.wallet == wallet.key() @ ErrorCode::Unauthorized,
-    )]
-    pub learner: Account<'info, Learner>,
-    
-    #[account(
-        init_if_needed,
-        payer = wallet,
-        space = 8 + CourseCompletion::LEN,
-        seeds = [b"completion", course.key().as_ref(), wallet.key().as_ref()],
+    /// Update the course submission bond and the share of a rejected
+    /// submission's bond forfeited to `bond_treasury`. Requires the
+    /// treasurer role.
+    pub fn update_bond_config(
+        ctx: Context<TreasurerAction>,
+        course_bond: Option<u64>,
+        rejection_penalty_bps: Option<u16>,
+        bond_treasury: Option<Pubkey>,
+    ) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+
+        if let Some(course_bond) = course_bond {
+            program_state.course_bond = course_bond;
+            msg!("Course bond set to: {}", course_bond);
+        }
+
+        if let Some(rejection_penalty_bps) = rejection_penalty_bps {
+            if rejection_penalty_bps > 10_000 {
+                return Err(ErrorCode::InvalidCurationConfig.into());
+            }
+            program_state.rejection_penalty_bps = rejection_penalty_bps;
+            msg!("Rejection penalty set to: {} bps", rejection_penalty_bps);
+        }
+
+        if let Some(bond_treasury) = bond_treasury {
+            program_state.bond_treasury = bond_treasury;
+            msg!("Bond treasury set to: {}", bond_treasury);
+        }
+
+        Ok(())
+    }
+
+    /// Trust `staking_pool` for reward boosts, or stop boosting rewards when
+    /// no pool is passed. The pool must stake the tenant's token mint.
+    pub fn set_staking_pool(ctx: Context<SetStakingPool>) -> Result<()> {
+        let staking_pool = ctx
+            .accounts
+            .staking_pool
+            .as_ref()
+            .map_or_else(Pubkey::default, |pool| pool.key());
+        ctx.accounts.program_state.staking_pool = staking_pool;
+
+        msg!("Staking pool set to: {}", staking_pool);
+
+        Ok(())
+    }
+
+    /// Pause the program immediately. Callable by any guardian (pauser role)
+    /// or the admin; only the admin can unpause through
+    /// `update_program_config`.
+    pub fn emergency_pause(ctx: Context<EmergencyPause>, reason: PauseReason) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+        let guardian = ctx.accounts.guardian.key();
+        let now = Clock::get()?.unix_timestamp;
+
+        if program_state.paused {
+            return Err(ErrorCode::ProgramPaused.into());
+        }
+
+        program_state.record_pause(guardian, reason, now);
+
+        emit!(ProgramPaused {
+            program_state: program_state.key(),
+            paused_by: guardian,
+            reason,
+            paused_at: now,
+        });
+
+        msg!("Program paused by {} ({:?})", guardian, reason);
+
+        Ok(())
+    }
+
+    /// Pause individual operations, given as `operation` flags, leaving the
+    /// rest running. Callable by guardians; only the admin can clear flags
+    /// through `update_program_config`.
+    pub fn pause_operations(ctx: Context<EmergencyPause>, operations: u8) -> Result<()> {
+        if operations == 0 || operations & !operation::ALL != 0 {
+            return Err(ErrorCode::InvalidOperations.into());
+        }
+
+        let program_state = &mut ctx.accounts.program_state;
+        program_state.paused_operations |= operations;
+
+        emit!(OperationsPaused {
+            program_state: program_state.key(),
+            paused_by: ctx.accounts.guardian.key(),
+            operations,
+            paused_operations: program_state.paused_operations,
+        });
+
+        msg!(
+            "Paused operations {:#07b} by {}",
+            operations,
+            ctx.accounts.guardian.key()
+        );
+
+        Ok(())
+    }
+
+    /// Return the current pause state for off-chain monitors
+    pub fn get_pause_state(ctx: Context<GetPauseState>) -> Result<PauseState> {
+        let program_state = &ctx.accounts.program_state;
+
+        Ok(PauseState {
+            paused: program_state.paused,
+            paused_operations: program_state.paused_operations,
+            paused_by: program_state.paused_by,
+            paused_at: program_state.paused_at,
+            reason: program_state.pause_reason,
+        })
+    }
+
+    /// Propose a new program authority. The pending authority must call
+    /// `accept_authority` once `delay` seconds have passed; until then the
+    /// current authority can cancel.
+    pub fn propose_authority(
+        ctx: Context<AdminAction>,
+        new_authority: Pubkey,
+        delay: u64,
+    ) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        if new_authority == Pubkey::default() {
+            return Err(ErrorCode::InvalidPendingAuthority.into());
+        }
+
+        program_state.pending_authority = new_authority;
+        program_state.authority_transfer_eta = i64::try_from(delay)
+            .ok()
+            .and_then(|delay| now.checked_add(delay))
+            .ok_or(ErrorCode::InvalidTimelock)?;
+
+        emit!(AuthorityTransferProposed {
+            program_state: program_state.key(),
+            authority: program_state.authority,
+            pending_authority: new_authority,
+            eta: program_state.authority_transfer_eta,
+        });
+
+        msg!("Proposed program authority transfer to: {}", new_authority);
+
+        Ok(())
+    }
+
+    /// Complete a proposed authority transfer, signed by the new authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+        let now = Clock::get()?.unix_timestamp;
+
+        if now < program_state.authority_transfer_eta {
+            return Err(ErrorCode::AuthorityTimelockActive.into());
+        }
+
+        let previous_authority = program_state.authority;
+        program_state.authority = program_state.pending_authority;
+        program_state.pending_authority = Pubkey::default();
+        program_state.authority_transfer_eta = 0;
+
+        emit!(AuthorityTransferAccepted {
+            program_state: program_state.key(),
+            previous_authority,
+            authority: program_state.authority,
+        });
+
+        msg!("Updated program authority to: {}", program_state.authority);
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<AdminAction>) -> Result<()> {
+        let program_state = &mut ctx.accounts.program_state;
+
+        if program_state.pending_authority == Pubkey::default() {
+            return Err(ErrorCode::NoPendingAuthority.into());
+        }
+
+        let pending_authority = program_state.pending_authority;
+        program_state.pending_authority = Pubkey::default();
+        program_state.authority_transfer_eta = 0;
+
+        emit!(AuthorityTransferCancelled {
+            program_state: program_state.key(),
+            authority: program_state.authority,
+            pending_authority,
+        });
+
+        msg!("Cancelled program authority transfer to: {}", pending_authority);
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct InitializeProgram<'info> {
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramState::LEN,
+        seeds = [b"program_state", tenant.key().as_ref()],
+        bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    /// Key identifying the platform instance; signing proves it is not
+    /// claimed by someone else
+    pub tenant: Signer<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateProgramState<'info> {
+    #[account(
+        mut,
+        owner = crate::ID @ ErrorCode::InvalidLegacyProgramState,
+    )]
+    /// CHECK: Keypair-based ProgramState from an earlier deployment, parsed in the handler
+    pub legacy_program_state: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + ProgramState::LEN,
+        seeds = [b"program_state", tenant.key().as_ref()],
+        bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    /// Key identifying the platform instance the state moves to
+    pub tenant: Signer<'info>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(institution_id: String)]
+pub struct CreateInstitution<'info> {
+    #[account(
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Institution::LEN,
+        seeds = [b"institution", program_state.key().as_ref(), institution_id.as_bytes()],
+        bump,
+    )]
+    pub institution: Account<'info, Institution>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InstitutionAdmin<'info> {
+    #[account(
+        mut,
+        seeds = [b"institution", institution.program_state.as_ref(), institution.institution_id.as_bytes()],
+        bump = institution.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub institution: Account<'info, Institution>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(course_id: String)]
+pub struct RegisterCourse<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.has_role(Role::CourseRegistrar, &authority.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        seeds = [b"institution", program_state.key().as_ref(), institution.institution_id.as_bytes()],
+        bump = institution.bump,
+        constraint = institution.can_manage(&authority.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub institution: Account<'info, Institution>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Course::LEN,
+        seeds = [b"course", institution.key().as_ref(), course_id.as_bytes()],
+        bump,
+    )]
+    pub course: Account<'info, Course>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CourseVersion::LEN,
+        seeds = [b"course_version", course.key().as_ref(), &1u32.to_le_bytes()],
+        bump,
+    )]
+    pub course_version: Account<'info, CourseVersion>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(course_id: String)]
+pub struct SubmitCourse<'info> {
+    #[account(
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        seeds = [b"institution", program_state.key().as_ref(), institution.institution_id.as_bytes()],
+        bump = institution.bump,
+        constraint = institution.can_manage(&educator.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub institution: Account<'info, Institution>,
+    
+    #[account(
+        init,
+        payer = educator,
+        space = 8 + Course::LEN,
+        seeds = [b"course", institution.key().as_ref(), course_id.as_bytes()],
+        bump,
+    )]
+    pub course: Account<'info, Course>,
+    
+    #[account(
+        init,
+        payer = educator,
+        space = 8 + CourseVersion::LEN,
+        seeds = [b"course_version", course.key().as_ref(), &1u32.to_le_bytes()],
+        bump,
+    )]
+    pub course_version: Account<'info, CourseVersion>,
+    
+    #[account(
+        init,
+        payer = educator,
+        seeds = [b"course_bond", course.key().as_ref()],
+        bump,
+        token::mint = token_mint,
+        token::authority = program_state,
+    )]
+    pub bond_escrow: Account<'info, TokenAccount>,
+    
+    #[account(address = program_state.token_mint)]
+    pub token_mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = educator_token_account.owner == educator.key(),
+        constraint = educator_token_account.mint == token_mint.key(),
+    )]
+    pub educator_token_account: Account<'info, TokenAccount>,
+    
+    #[account(mut)]
+    pub educator: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ReviewCourse<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.has_role(Role::Reviewer, &reviewer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        seeds = [b"course", course.institution.as_ref(), course.course_id.as_bytes()],
+        bump,
+        has_one = program_state @ ErrorCode::InvalidTenant,
+    )]
+    pub course: Account<'info, Course>,
+    
+    #[account(mut, address = course.institution)]
+    pub institution: Account<'info, Institution>,
+    
+    #[account(
+        init,
+        payer = reviewer,
+        space = 8 + CourseReview::LEN,
+        seeds = [
+            b"course_review",
+            course.key().as_ref(),
+            reviewer.key().as_ref(),
+            &course.review_round.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub course_review: Account<'info, CourseReview>,
+    
+    #[account(mut)]
+    pub reviewer: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimCourseBond<'info> {
+    #[account(
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        seeds = [b"course", course.institution.as_ref(), course.course_id.as_bytes()],
+        bump,
+        has_one = program_state @ ErrorCode::InvalidTenant,
+        constraint = course.authority == educator.key() @ ErrorCode::Unauthorized,
+    )]
+    pub course: Account<'info, Course>,
+    
+    #[account(
+        mut,
+        seeds = [b"course_bond", course.key().as_ref()],
+        bump,
+    )]
+    pub bond_escrow: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        constraint = educator_token_account.owner == educator.key(),
+        constraint = educator_token_account.mint == program_state.token_mint,
+    )]
+    pub educator_token_account: Account<'info, TokenAccount>,
+    
+    /// Receives the forfeited share of a rejected course's bond
+    #[account(
+        mut,
+        address = program_state.bond_treasury @ ErrorCode::InvalidBondTreasury,
+    )]
+    pub bond_treasury: Option<Account<'info, TokenAccount>>,
+    
+    #[account(mut)]
+    pub educator: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterLearner<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        init,
+        payer = wallet,
+        space = 8 + Learner::LEN,
+        seeds = [b"learner", program_state.key().as_ref(), wallet.key().as_ref()],
+        bump,
+    )]
+    pub learner: Account<'info, Learner>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CompleteCourse<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        has_one = program_state @ ErrorCode::InvalidTenant,
+        constraint = course.active @ ErrorCode::CourseInactive,
+    )]
+    pub course: Account<'info, Course>,
+    
+    #[account(mut, address = course.institution)]
+    pub institution: Account<'info, Institution>,
+    
+    #[account(
+        mut,
+        seeds = [b"learner", program_state.key().as_ref(), wallet.key().as_ref()],
+        bump,
+        constraint = learner.wallet == wallet.key() @ ErrorCode::Unauthorized,
+    )]
+    pub learner: Account<'info, Learner>,
+    
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = 8 + CourseCompletion::LEN,
+        seeds = [b"completion", program_state.key().as_ref(), course.key().as_ref(), wallet.key().as_ref()],
+        bump,
+    )]
+    pub course_completion: Account<'info, CourseCompletion>,
+    
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+    
+    // Token program accounts
+    #[account(
+        mut,
+        address = program_state.token_mint,
+    )]
+    pub token_mint: Account<'info, Mint>,
+    
+    #[account(mut)]
+    /// CHECK: The token program's MintConfig for `token_mint`, validated by solearn_token
+    pub mint_config: UncheckedAccount<'info>,
+    
+    /// CHECK: The token program's mint authority PDA, validated by solearn_token
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The program state's MinterAllowance, validated by solearn_token
+    pub minter_allowance: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The allocation bucket bound to the minter allowance, validated by solearn_token
+    pub allocation_bucket: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The mint's EmissionSchedule, or the solearn_token program id when
+    /// the mint has none; validated by solearn_token
+    pub emission_schedule: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = learner_token_account.owner == wallet.key(),
+        constraint = learner_token_account.mint == token_mint.key(),
+    )]
+    pub learner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        address = program_state.token_program,
+    )]
+    pub token_program: Program<'info, Token>,
+    
+    pub solearn_token_program: Program<'info, SolearnToken>,
+    
+    #[account(address = solana_program::sysvar::instructions::ID)]
+    /// CHECK: The instructions sysvar, used to read the assessor's ed25519 signature
+    pub instructions_sysvar: UncheckedAccount<'info>,
+    
+    /// The tenant's trusted staking pool, passed with `stake_position` to
+    /// boost the reward by the learner's staking tier
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    
+    pub stake_position: Option<Account<'info, StakePosition>>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimCompletionReward<'info> {
+    #[account(
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        seeds = [b"completion", program_state.key().as_ref(), course_completion.course.as_ref(), wallet.key().as_ref()],
         bump,
+        constraint = course_completion.completed @ ErrorCode::Unauthorized,
     )]
     pub course_completion: Account<'info, CourseCompletion>,
     
-    #[account(mut)]
+    #[account(address = course_completion.course)]
+    pub course: Account<'info, Course>,
+    
     pub wallet: Signer<'info>,
     
     // Token program accounts
@@ -361,6 +1479,26 @@ pub struct CompleteCourse<'info> {
     )]
     pub token_mint: Account<'info, Mint>,
     
+    #[account(mut)]
+    /// CHECK: The token program's MintConfig for `token_mint`, validated by solearn_token
+    pub mint_config: UncheckedAccount<'info>,
+    
+    /// CHECK: The token program's mint authority PDA, validated by solearn_token
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The program state's MinterAllowance, validated by solearn_token
+    pub minter_allowance: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The allocation bucket bound to the minter allowance, validated by solearn_token
+    pub allocation_bucket: UncheckedAccount<'info>,
+    
+    #[account(mut)]
+    /// CHECK: The mint's EmissionSchedule, or the solearn_token program id when
+    /// the mint has none; validated by solearn_token
+    pub emission_schedule: UncheckedAccount<'info>,
+    
     #[account(
         mut,
         constraint = learner_token_account.owner == wallet.key(),
@@ -373,25 +1511,51 @@ pub struct CompleteCourse<'info> {
     )]
     pub token_program: Program<'info, Token>,
     
-    pub system_program: Program<'info, System>,
+    pub solearn_token_program: Program<'info, SolearnToken>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateCourse<'info> {
     #[account(
         mut,
-        constraint = course.authority == authority.key() @ ErrorCode::Unauthorized,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        mut,
+        has_one = program_state @ ErrorCode::InvalidTenant,
+        constraint = course.authority == authority.key()
+            || institution.can_manage(&authority.key()) @ ErrorCode::Unauthorized,
     )]
     pub course: Account<'info, Course>,
     
+    #[account(mut, address = course.institution)]
+    pub institution: Account<'info, Institution>,
+    
+    /// Account for the next version, required when publishing new content
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + CourseVersion::LEN,
+        seeds = [b"course_version", course.key().as_ref(), &(course.version + 1).to_le_bytes()],
+        bump,
+    )]
+    pub course_version: Option<Account<'info, CourseVersion>>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Option<Program<'info, System>>,
 }
 
 #[derive(Accounts)]
 pub struct UpdateProgramConfig<'info> {
     #[account(
         mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
         constraint = program_state.authority == authority.key() @ ErrorCode::Unauthorized,
     )]
     pub program_state: Account<'info, ProgramState>,
@@ -400,25 +1564,249 @@ pub struct UpdateProgramConfig<'info> {
     pub authority: Signer<'info>,
 }
 
+#[derive(Accounts)]
+pub struct TreasurerAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.has_role(Role::Treasurer, &treasurer.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub treasurer: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetStakingPool<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    #[account(
+        constraint = staking_pool.mint == program_state.token_mint @ ErrorCode::InvalidStakePosition,
+    )]
+    pub staking_pool: Option<Account<'info, StakingPool>>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.has_role(Role::Pauser, &guardian.key())
+            || program_state.has_role(Role::Admin, &guardian.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetPauseState<'info> {
+    #[account(
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        has_one = pending_authority @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct AdminAction<'info> {
+    #[account(
+        mut,
+        seeds = [b"program_state", program_state.tenant.as_ref()],
+        bump = program_state.bump,
+        constraint = program_state.authority == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub program_state: Account<'info, ProgramState>,
+    
+    pub authority: Signer<'info>,
+}
+
 #[account]
 #[derive(Default)]
 pub struct ProgramState {
+    pub tenant: Pubkey,                // 32 bytes, seeds this state and its accounts
     pub authority: Pubkey,             // 32 bytes
     pub token_mint: Pubkey,            // 32 bytes
     pub token_program: Pubkey,         // 32 bytes
     pub total_courses: u64,            // 8 bytes
     pub total_learners: u64,           // 8 bytes
-    pub paused: bool,                  // 1 byte
+    pub paused: bool,                  // 1 byte, pauses every operation
+    pub paused_operations: u8,         // 1 byte, `operation` flags
+    pub paused_by: Pubkey,             // 32 bytes, default while not paused
+    pub paused_at: i64,                // 8 bytes
+    pub pause_reason: PauseReason,     // 1 byte
+    pub pausers: Vec<Pubkey>,          // 4 + 32 * MAX_ROLE_MEMBERS bytes
+    pub course_registrars: Vec<Pubkey>, // 4 + 32 * MAX_ROLE_MEMBERS bytes
+    pub assessors: Vec<Pubkey>,        // 4 + 32 * MAX_ROLE_MEMBERS bytes
+    pub treasurers: Vec<Pubkey>,       // 4 + 32 * MAX_ROLE_MEMBERS bytes
+    pub reviewers: Vec<Pubkey>,        // 4 + 32 * MAX_ROLE_MEMBERS bytes
+    pub course_bond: u64,              // 8 bytes, LEARN bonded by each course submission
+    pub review_quorum: u8,             // 1 byte
+    pub rejection_penalty_bps: u16,    // 2 bytes
+    pub bond_treasury: Pubkey,         // 32 bytes, token account for forfeited bonds
+    pub staking_pool: Pubkey,          // 32 bytes, pool trusted for reward boosts, default when unset
+    pub pending_authority: Pubkey,     // 32 bytes, default when no transfer is pending
+    pub authority_transfer_eta: i64,   // 8 bytes
+    pub bump: u8,                      // 1 byte
 }
 
 impl ProgramState {
+    pub const MAX_ROLE_MEMBERS: usize = 10;
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 1 + 1 + 32 + 8 + 1
+        + 5 * (4 + 32 * Self::MAX_ROLE_MEMBERS) + 8 + 1 + 2 + 32 + 32 + 32 + 8 + 1;
+
+    /// Fail if the whole program or the given `operation` flag is paused
+    pub fn require_active(&self, operation: u8) -> Result<()> {
+        if self.paused {
+            return Err(ErrorCode::ProgramPaused.into());
+        }
+        if self.paused_operations & operation == 0 {
+            return Ok(());
+        }
+
+        let error = match operation {
+            operation::REGISTRATION => ErrorCode::RegistrationPaused,
+            operation::COMPLETION => ErrorCode::CompletionPaused,
+            operation::REWARD_MINTING => ErrorCode::RewardMintingPaused,
+            operation::COURSE_UPDATES => ErrorCode::CourseUpdatesPaused,
+            operation::CURATION => ErrorCode::CurationPaused,
+            _ => ErrorCode::ProgramPaused,
+        };
+        Err(error.into())
+    }
+
+    /// Mark the program paused by `paused_by`
+    pub fn record_pause(&mut self, paused_by: Pubkey, reason: PauseReason, now: i64) {
+        self.paused = true;
+        self.paused_by = paused_by;
+        self.paused_at = now;
+        self.pause_reason = reason;
+    }
+
+    /// Clear the pause state
+    pub fn record_unpause(&mut self) {
+        self.paused = false;
+        self.paused_by = Pubkey::default();
+        self.paused_at = 0;
+        self.pause_reason = PauseReason::default();
+    }
+
+    /// Whether `key` holds `role`. The admin is the program authority.
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        match role {
+            Role::Admin => self.authority == *key,
+            Role::Pauser => self.pausers.contains(key),
+            Role::CourseRegistrar => self.course_registrars.contains(key),
+            Role::Assessor => self.assessors.contains(key),
+            Role::Treasurer => self.treasurers.contains(key),
+            Role::Reviewer => self.reviewers.contains(key),
+        }
+    }
+
+    /// Key set of a grantable role; `None` for the admin role
+    pub fn role_members_mut(&mut self, role: Role) -> Option<&mut Vec<Pubkey>> {
+        match role {
+            Role::Admin => None,
+            Role::Pauser => Some(&mut self.pausers),
+            Role::CourseRegistrar => Some(&mut self.course_registrars),
+            Role::Assessor => Some(&mut self.assessors),
+            Role::Treasurer => Some(&mut self.treasurers),
+            Role::Reviewer => Some(&mut self.reviewers),
+        }
+    }
+}
+
+/// Roles that gate program instructions
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Role {
+    /// Rotates the authority, grants roles and reconfigures the program
+    Admin,
+    /// Guardian key that can pause the program in an emergency
+    Pauser,
+    /// Registers new courses
+    CourseRegistrar,
+    /// Signs course completion attestations
+    Assessor,
+    /// Sets the course bond, the rejection penalty and the bond treasury
+    Treasurer,
+    /// Approves or rejects submitted courses
+    Reviewer,
+}
+
+/// Layout of the keypair-based ProgramState created before it moved to a PDA.
+/// Only read by `migrate_program_state`.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct LegacyProgramState {
+    pub authority: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_program: Pubkey,
+    pub total_courses: u64,
+    pub total_learners: u64,
+    pub paused: bool,
+}
+
+impl LegacyProgramState {
     pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 1;
 }
 
+#[account]
+#[derive(Default)]
+pub struct Institution {
+    pub program_state: Pubkey,         // 32 bytes, tenant
+    pub authority: Pubkey,             // 32 bytes
+    pub institution_id: String,        // 4 + 20 = 24 bytes
+    pub name: String,                  // 4 + 100 = 104 bytes
+    pub metadata_uri: String,          // 4 + 200 = 204 bytes
+    pub managers: Vec<Pubkey>,         // 4 + 32 * MAX_MANAGERS bytes
+    pub total_courses: u64,            // 8 bytes, approved courses
+    pub total_completions: u64,        // 8 bytes
+    pub total_rewards: u64,            // 8 bytes, tokens earned across its courses
+    pub created_at: i64,               // 8 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl Institution {
+    pub const MAX_ID_LEN: usize = 20;
+    pub const MAX_NAME_LEN: usize = 100;
+    pub const MAX_URI_LEN: usize = 200;
+    pub const MAX_MANAGERS: usize = 10;
+    pub const LEN: usize = 32 + 32 + 24 + 104 + 204 + (4 + 32 * Self::MAX_MANAGERS) + 8 + 8 + 8 + 8 + 1;
+
+    /// Whether `key` can manage the institution's courses
+    pub fn can_manage(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.managers.contains(key)
+    }
+}
+
 #[account]
 #[derive(Default)]
 pub struct Course {
+    pub program_state: Pubkey,         // 32 bytes, tenant
     pub authority: Pubkey,             // 32 bytes
+    pub institution: Pubkey,           // 32 bytes
     pub course_id: String,             // 4 + 20 = 24 bytes
     pub title: String,                 // 4 + 100 = 104 bytes
     pub description: String,           // 4 + 200 = 204 bytes
@@ -427,10 +1815,99 @@ pub struct Course {
     pub active: bool,                  // 1 byte
     pub completed_count: u64,          // 8 bytes
     pub created_at: i64,               // 8 bytes
+    pub status: CourseStatus,          // 1 byte
+    pub bond_amount: u64,              // 8 bytes, held in the course bond escrow
+    pub approvals: u8,                 // 1 byte
+    pub rejections: u8,                // 1 byte
+    pub reviewed_at: i64,              // 8 bytes
+    pub version: u32,                  // 4 bytes, current content version
+    pub content_hash: [u8; 32],        // 32 bytes, content manifest hash
+    pub content_uri: String,           // 4 + 100 = 104 bytes, IPFS/Arweave CID or URI
+    pub accept_previous_versions: bool, // 1 byte, whether older-version completions count
+    pub review_round: u32,             // 4 bytes, bumped when changed terms need a new review
 }
 
 impl Course {
-    pub const LEN: usize = 32 + 24 + 104 + 204 + 8 + 1 + 1 + 8 + 8;
+    pub const LEN: usize = 32 + 32 + 32 + 24 + 104 + 204 + 8 + 1 + 1 + 8 + 8 + 1 + 8 + 1 + 1 + 8
+        + 4 + 32 + 104 + 1 + 4;
+
+    /// Whether a completion of `version` counts: the current version always
+    /// does, earlier ones only while `accept_previous_versions` is set
+    pub fn accepts_version(&self, version: u32) -> bool {
+        version == self.version
+            || (self.accept_previous_versions && version >= 1 && version < self.version)
+    }
+}
+
+/// Content of a course version
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct CourseContent {
+    pub content_hash: [u8; 32],
+    pub content_uri: String,
+}
+
+impl CourseContent {
+    pub const MAX_URI_LEN: usize = 100;
+}
+
+/// A published version of a course's content, kept as history
+#[account]
+#[derive(Default)]
+pub struct CourseVersion {
+    pub course: Pubkey,                // 32 bytes
+    pub version: u32,                  // 4 bytes
+    pub content_hash: [u8; 32],        // 32 bytes
+    pub content_uri: String,           // 4 + 100 = 104 bytes
+    pub published_at: i64,             // 8 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl CourseVersion {
+    pub const LEN: usize = 32 + 4 + 32 + 104 + 8 + 1;
+}
+
+#[event]
+pub struct CourseVersionPublished {
+    pub course: Pubkey,
+    pub version: u32,
+    pub content_hash: [u8; 32],
+}
+
+/// Review state of a course. Only approved courses accept completions.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CourseStatus {
+    #[default]
+    Pending,
+    Approved,
+    Rejected,
+}
+
+#[account]
+#[derive(Default)]
+pub struct CourseReview {
+    pub course: Pubkey,                // 32 bytes
+    pub reviewer: Pubkey,              // 32 bytes
+    pub approve: bool,                 // 1 byte
+    pub reviewed_at: i64,              // 8 bytes
+}
+
+impl CourseReview {
+    pub const LEN: usize = 32 + 32 + 1 + 8;
+}
+
+#[event]
+pub struct CourseSubmitted {
+    pub course: Pubkey,
+    pub educator: Pubkey,
+    pub bond_amount: u64,
+}
+
+#[event]
+pub struct CourseReviewed {
+    pub course: Pubkey,
+    pub status: CourseStatus,
+    pub approvals: u8,
+    pub rejections: u8,
 }
 
 #[account]
@@ -453,16 +1930,286 @@ impl Learner {
 pub struct CourseCompletion {
     pub learner: Pubkey,               // 32 bytes
     pub course: Pubkey,                // 32 bytes
+    pub course_version: u32,           // 4 bytes
     pub completed: bool,               // 1 byte
     pub score: u8,                     // 1 byte
     pub evidence_hash: String,         // 4 + 64 = 68 bytes
     pub completed_at: i64,             // 8 bytes
     pub xp_earned: u64,                // 8 bytes
     pub tokens_earned: u64,            // 8 bytes
+    pub assessor: Pubkey,              // 32 bytes
+    pub attestation_nonce: u64,        // 8 bytes
+    pub reward_minted: bool,           // 1 byte
 }
 
 impl CourseCompletion {
-    pub const LEN: usize = 32 + 32 + 1 + 1 + 68 + 8 + 8 + 8;
+    pub const MAX_EVIDENCE_HASH_LEN: usize = 64;
+    pub const LEN: usize = 32 + 32 + 4 + 1 + 1 + 68 + 8 + 8 + 8 + 32 + 8 + 1;
+}
+
+/// Operations that can be paused individually with
+/// `ProgramState::paused_operations`
+pub mod operation {
+    /// `register_course` and `register_learner`
+    pub const REGISTRATION: u8 = 1 << 0;
+    /// `complete_course`
+    pub const COMPLETION: u8 = 1 << 1;
+    /// Reward minting in `complete_course` and `claim_completion_reward`
+    pub const REWARD_MINTING: u8 = 1 << 2;
+    /// `update_course`
+    pub const COURSE_UPDATES: u8 = 1 << 3;
+    /// `review_course` and `reclaim_course_bond`
+    pub const CURATION: u8 = 1 << 4;
+
+    pub const ALL: u8 = REGISTRATION | COMPLETION | REWARD_MINTING | COURSE_UPDATES | CURATION;
+}
+
+#[event]
+pub struct OperationsPaused {
+    pub program_state: Pubkey,
+    pub paused_by: Pubkey,
+    pub operations: u8,
+    pub paused_operations: u8,
+}
+
+/// Why the program was paused
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseReason {
+    /// Paused by the admin through `update_program_config`
+    #[default]
+    Administrative,
+    SecurityIncident,
+    AnomalousActivity,
+    Upgrade,
+    Other,
+}
+
+/// Return data of `get_pause_state`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PauseState {
+    pub paused: bool,
+    /// `operation` flags paused individually while the program is running
+    pub paused_operations: u8,
+    pub paused_by: Pubkey,
+    pub paused_at: i64,
+    pub reason: PauseReason,
+}
+
+#[event]
+pub struct ProgramPaused {
+    pub program_state: Pubkey,
+    pub paused_by: Pubkey,
+    pub reason: PauseReason,
+    pub paused_at: i64,
+}
+
+#[event]
+pub struct ProgramUnpaused {
+    pub program_state: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub program_state: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub eta: i64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub program_state: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub program_state: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Message an assessor signs with ed25519 to attest a course completion.
+/// Clients borsh-serialize this struct and pass it to the ed25519 program
+/// in the instruction right before `complete_course`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct CompletionAttestation {
+    pub learner: Pubkey,
+    pub course: Pubkey,
+    pub course_version: u32,
+    pub score: u8,
+    pub evidence_hash: String,
+    pub nonce: u64,
+}
+
+/// Size of the ed25519 instruction header for a single signature:
+/// signature count, padding and seven u16 offsets
+const ED25519_SINGLE_SIGNATURE_HEADER_LEN: usize = 2 + 14;
+
+/// Check that the instruction before the current one is an ed25519 program
+/// instruction in which a registered assessor signed `attestation`.
+/// Returns the assessor's key.
+fn verify_assessor_attestation(
+    instructions_sysvar: &AccountInfo,
+    program_state: &ProgramState,
+    attestation: &CompletionAttestation,
+) -> Result<Pubkey> {
+    let current_index = load_current_index_checked(instructions_sysvar)?;
+    if current_index == 0 {
+        return Err(ErrorCode::InvalidAssessorAttestation.into());
+    }
+
+    let ed25519_ix = load_instruction_at_checked(usize::from(current_index - 1), instructions_sysvar)?;
+    if ed25519_ix.program_id != ed25519_program::ID || !ed25519_ix.accounts.is_empty() {
+        return Err(ErrorCode::InvalidAssessorAttestation.into());
+    }
+
+    // Expect exactly one signature whose data all lives in this instruction
+    let data = &ed25519_ix.data;
+    if data.len() < ED25519_SINGLE_SIGNATURE_HEADER_LEN || data[0] != 1 {
+        return Err(ErrorCode::InvalidAssessorAttestation.into());
+    }
+    let read_u16 = |offset: usize| usize::from(u16::from_le_bytes([data[offset], data[offset + 1]]));
+    let signature_ix_index = read_u16(4);
+    let public_key_offset = read_u16(6);
+    let public_key_ix_index = read_u16(8);
+    let message_offset = read_u16(10);
+    let message_size = read_u16(12);
+    let message_ix_index = read_u16(14);
+
+    let this_instruction = usize::from(u16::MAX);
+    if signature_ix_index != this_instruction
+        || public_key_ix_index != this_instruction
+        || message_ix_index != this_instruction
+    {
+        return Err(ErrorCode::InvalidAssessorAttestation.into());
+    }
+
+    let assessor = data
+        .get(public_key_offset..public_key_offset + 32)
+        .and_then(|bytes| Pubkey::try_from(bytes).ok())
+        .ok_or(ErrorCode::InvalidAssessorAttestation)?;
+    if !program_state.has_role(Role::Assessor, &assessor) {
+        return Err(ErrorCode::AssessorNotRegistered.into());
+    }
+
+    let message = data
+        .get(message_offset..message_offset + message_size)
+        .ok_or(ErrorCode::InvalidAssessorAttestation)?;
+    if message != attestation.try_to_vec()?.as_slice() {
+        return Err(ErrorCode::InvalidAssessorAttestation.into());
+    }
+
+    Ok(assessor)
+}
+
+/// Scale a course reward by the staking tier boost of `wallet` in the trusted
+/// pool. Without a stake position the reward is unchanged.
+fn boosted_reward(
+    reward_amount: u64,
+    wallet: &Pubkey,
+    trusted_pool: &Pubkey,
+    staking_pool: &Option<Account<StakingPool>>,
+    stake_position: &Option<Account<StakePosition>>,
+) -> Result<u64> {
+    match (staking_pool, stake_position) {
+        (None, None) => Ok(reward_amount),
+        // Only the pool pinned by the admin counts. Positions only exist at
+        // their PDAs, so matching the pool and owner identifies the learner's
+        // own position.
+        (Some(pool), Some(position))
+            if pool.key() == *trusted_pool
+                && position.pool == pool.key()
+                && position.owner == *wallet =>
+        {
+            let boosted = pool.boosted_amount(reward_amount, position.amount);
+            if boosted > reward_amount {
+                msg!(
+                    "Staking boost of {} bps applied",
+                    pool.boost_bps(position.amount)
+                );
+            }
+            Ok(boosted)
+        }
+        _ => Err(ErrorCode::InvalidStakePosition.into()),
+    }
+}
+
+/// Check course metadata lengths and the required score
+fn validate_course(course_id: &str, title: &str, description: &str, required_score: u8) -> Result<()> {
+    if course_id.len() > 20 {
+        return Err(ErrorCode::CourseIdTooLong.into());
+    }
+    if title.len() > 100 {
+        return Err(ErrorCode::CourseTitleTooLong.into());
+    }
+    if description.len() > 200 {
+        return Err(ErrorCode::CourseDescriptionTooLong.into());
+    }
+    if required_score > 100 {
+        return Err(ErrorCode::InvalidRequiredScore.into());
+    }
+
+    Ok(())
+}
+
+/// Publish `content` as the next version of `course`, recording it in
+/// `course_version`
+fn publish_version(
+    course: &mut Account<Course>,
+    course_version: &mut Account<CourseVersion>,
+    content: CourseContent,
+    bump: u8,
+    now: i64,
+) -> Result<()> {
+    if content.content_uri.len() > CourseContent::MAX_URI_LEN {
+        return Err(ErrorCode::ContentUriTooLong.into());
+    }
+
+    course.version = course.version.checked_add(1).unwrap();
+    course.content_hash = content.content_hash;
+    course.content_uri = content.content_uri.clone();
+
+    course_version.course = course.key();
+    course_version.version = course.version;
+    course_version.content_hash = content.content_hash;
+    course_version.content_uri = content.content_uri;
+    course_version.published_at = now;
+    course_version.bump = bump;
+
+    emit!(CourseVersionPublished {
+        course: course.key(),
+        version: course.version,
+        content_hash: course.content_hash,
+    });
+
+    msg!("Published version {} of course {}", course.version, course.title);
+
+    Ok(())
+}
+
+/// Mint `amount` reward tokens to `recipient` through the LEARN token program
+/// so the supply cap, pause switch and rate limit in its MintConfig all apply.
+/// The program state PDA holds the minter allowance and signs the CPI.
+fn mint_reward<'info>(
+    program_state: &Account<'info, ProgramState>,
+    solearn_token_program: AccountInfo<'info>,
+    cpi_accounts: solearn_token::cpi::accounts::MintTokens<'info>,
+    amount: u64,
+    recipient: Pubkey,
+) -> Result<()> {
+    let seeds = &[b"program_state".as_ref(), program_state.tenant.as_ref(), &[program_state.bump]];
+    let signer = &[&seeds[..]];
+
+    let cpi_ctx = CpiContext::new_with_signer(solearn_token_program, cpi_accounts, signer);
+
+    solearn_token::cpi::mint_tokens(cpi_ctx, amount, recipient)?;
+
+    msg!("Minted {} tokens to learner {}", amount, recipient);
+
+    Ok(())
 }
 
 #[error_code]
@@ -485,6 +2232,39 @@ pub enum ErrorCode {
     #[msg("Course is inactive")]
     CourseInactive,
     
+    #[msg("Completions of this course version no longer count")]
+    CourseVersionNotAccepted,
+    
+    #[msg("Course version account is required to publish new content, and only then")]
+    MissingCourseVersion,
+    
+    #[msg("Content URI too long")]
+    ContentUriTooLong,
+    
+    #[msg("Account belongs to another tenant")]
+    InvalidTenant,
+    
+    #[msg("Institution ID too long")]
+    InstitutionIdTooLong,
+    
+    #[msg("Institution name or metadata URI too long")]
+    InstitutionMetadataTooLong,
+    
+    #[msg("Course has not been approved")]
+    CourseNotApproved,
+    
+    #[msg("Course is not pending review")]
+    CourseNotPending,
+    
+    #[msg("Course has not been reviewed yet")]
+    CourseNotReviewed,
+    
+    #[msg("Invalid curation config")]
+    InvalidCurationConfig,
+    
+    #[msg("Missing or invalid bond treasury account")]
+    InvalidBondTreasury,
+    
     #[msg("Score too low to pass course")]
     ScoreTooLow,
     
@@ -493,4 +2273,67 @@ pub enum ErrorCode {
     
     #[msg("Program is paused")]
     ProgramPaused,
+    
+    #[msg("Registration is paused")]
+    RegistrationPaused,
+    
+    #[msg("Course completion is paused")]
+    CompletionPaused,
+    
+    #[msg("Reward minting is paused")]
+    RewardMintingPaused,
+    
+    #[msg("Course updates are paused")]
+    CourseUpdatesPaused,
+    
+    #[msg("Course reviews and bond reclaims are paused")]
+    CurationPaused,
+    
+    #[msg("Invalid operation flags")]
+    InvalidOperations,
+    
+    #[msg("Reward for this completion has already been minted")]
+    RewardAlreadyMinted,
+    
+    #[msg("Stake position does not belong to this learner and mint")]
+    InvalidStakePosition,
+    
+    #[msg("Completion is missing a valid assessor attestation")]
+    InvalidAssessorAttestation,
+    
+    #[msg("Assessor is not registered")]
+    AssessorNotRegistered,
+    
+    #[msg("Role is already granted to this key")]
+    RoleAlreadyGranted,
+    
+    #[msg("Role is not granted to this key")]
+    RoleNotGranted,
+    
+    #[msg("Too many keys hold this role")]
+    TooManyRoleMembers,
+    
+    #[msg("This role cannot be granted or revoked")]
+    InvalidRole,
+    
+    #[msg("Account is not a legacy program state")]
+    InvalidLegacyProgramState,
+    
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    
+    #[msg("Invalid timelock delay")]
+    InvalidTimelock,
+    
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Authority transfer timelock has not elapsed")]
+    AuthorityTimelockActive,
+    
+    #[msg("Course ID too long")]
+    CourseIdTooLong,
+    
+    #[msg("Evidence hash too long")]
+    EvidenceHashTooLong,
 } 
\ No newline at end of file
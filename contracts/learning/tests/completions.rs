@@ -0,0 +1,769 @@
+use anchor_lang::{AccountDeserialize, AnchorSerialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    ed25519_program,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signature, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_learning::{operation, CompletionAttestation, CourseCompletion, CourseContent, Role};
+
+const COURSE_ID: &str = "INTRO101";
+const INSTITUTION_ID: &str = "UNI";
+const REWARD_AMOUNT: u64 = 100;
+const REQUIRED_SCORE: u8 = 70;
+const EVIDENCE_HASH: &str = "bafkreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku";
+
+/// A learner wallet registered with the tenant and its LEARN token account
+struct TestLearner {
+    wallet: Keypair,
+    token_account: Pubkey,
+}
+
+/// One tenant with a registered course, its admin (who is also the course
+/// registrar and reviewer) and a registered assessor. Reward minting is
+/// paused so completions are recorded without minting through solearn_token.
+struct Fixture {
+    banks_client: BanksClient,
+    tenant: Keypair,
+    admin: Keypair,
+    assessor: Keypair,
+    mint: Pubkey,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+fn content(version: u8) -> CourseContent {
+    CourseContent {
+        content_hash: [version; 32],
+        content_uri: format!("ipfs://course-content-v{}", version),
+    }
+}
+
+/// Build an ed25519 program instruction carrying one signature with the
+/// public key, signature and message all stored in the instruction itself
+fn ed25519_instruction(public_key: &Pubkey, signature: &Signature, message: &[u8]) -> Instruction {
+    let public_key_offset: u16 = 16;
+    let signature_offset = public_key_offset + 32;
+    let message_offset = signature_offset + 64;
+    let mut data = vec![1, 0];
+    for value in [
+        signature_offset,
+        u16::MAX,
+        public_key_offset,
+        u16::MAX,
+        message_offset,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(public_key.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+    Instruction {
+        program_id: ed25519_program::id(),
+        accounts: vec![],
+        data,
+    }
+}
+
+fn staking_pool(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"staking-pool", mint.as_ref()], &solearn_staking::id()).0
+}
+
+fn stake_position(pool: &Pubkey, owner: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"stake", pool.as_ref(), owner.as_ref()], &solearn_staking::id()).0
+}
+
+impl Fixture {
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = self.banks_client.get_latest_blockhash().await.unwrap();
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            all_signers.as_slice(),
+            recent_blockhash,
+        );
+        self.banks_client.process_transaction(transaction).await
+    }
+
+    fn program_state(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"program_state", self.tenant.pubkey().as_ref()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn institution(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"institution", self.program_state().as_ref(), INSTITUTION_ID.as_bytes()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn course(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"course", self.institution().as_ref(), COURSE_ID.as_bytes()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn course_version(&self, version: u32) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"course_version", self.course().as_ref(), &version.to_le_bytes()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn learner(&self, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"learner", self.program_state().as_ref(), wallet.as_ref()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn completion(&self, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"completion",
+                self.program_state().as_ref(),
+                self.course().as_ref(),
+                wallet.as_ref(),
+            ],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    async fn course_completion(&mut self, wallet: &Pubkey) -> CourseCompletion {
+        let address = self.completion(wallet);
+        let account = self.banks_client.get_account(address).await.unwrap().unwrap();
+        CourseCompletion::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn admin_action(&self, data: Vec<u8>) -> Instruction {
+        Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::AdminAction {
+                program_state: self.program_state(),
+                authority: self.admin.pubkey(),
+            }
+            .to_account_metas(None),
+            data,
+        }
+    }
+
+    fn grant_role(&self, role: Role, key: Pubkey) -> Instruction {
+        self.admin_action(solearn_learning::instruction::GrantRole { role, key }.data())
+    }
+
+    /// Fund a new wallet, register it as a learner and give it a token
+    /// account for the tenant's mint
+    async fn new_learner(&mut self) -> TestLearner {
+        let wallet = Keypair::new();
+        let token_account = Keypair::new();
+        let admin = self.admin.insecure_clone();
+        let rent = self.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::transfer(&admin.pubkey(), &wallet.pubkey(), LAMPORTS_PER_SOL),
+            system_instruction::create_account(
+                &admin.pubkey(),
+                &token_account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token_account.pubkey(),
+                &self.mint,
+                &wallet.pubkey(),
+            )
+            .unwrap(),
+            Instruction {
+                program_id: solearn_learning::id(),
+                accounts: solearn_learning::accounts::RegisterLearner {
+                    program_state: self.program_state(),
+                    learner: self.learner(&wallet.pubkey()),
+                    wallet: wallet.pubkey(),
+                    system_program: system_program::id(),
+                }
+                .to_account_metas(None),
+                data: solearn_learning::instruction::RegisterLearner {
+                    learner_name: "Ada".to_string(),
+                }
+                .data(),
+            },
+        ];
+        self.process(&instructions, &admin, &[&token_account, &wallet])
+            .await
+            .unwrap();
+
+        TestLearner {
+            wallet,
+            token_account: token_account.pubkey(),
+        }
+    }
+
+    fn attestation(&self, learner: &TestLearner, course_version: u32, score: u8) -> CompletionAttestation {
+        CompletionAttestation {
+            learner: learner.wallet.pubkey(),
+            course: self.course(),
+            course_version,
+            score,
+            evidence_hash: EVIDENCE_HASH.to_string(),
+            nonce: 1,
+        }
+    }
+
+    fn complete_course(&self, learner: &TestLearner, attestation: &CompletionAttestation) -> Instruction {
+        self.complete_course_staked(learner, attestation, None)
+    }
+
+    /// Complete the course passing the learner's stake position in
+    /// `staking_pool` for a reward boost
+    fn complete_course_staked(
+        &self,
+        learner: &TestLearner,
+        attestation: &CompletionAttestation,
+        staking_pool: Option<Pubkey>,
+    ) -> Instruction {
+        let wallet = learner.wallet.pubkey();
+        let stake_position = staking_pool.map(|pool| stake_position(&pool, &wallet));
+        Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::CompleteCourse {
+                program_state: self.program_state(),
+                course: self.course(),
+                institution: self.institution(),
+                learner: self.learner(&wallet),
+                course_completion: self.completion(&wallet),
+                wallet,
+                token_mint: self.mint,
+                // Reward minting is paused, so the token program accounts
+                // are never read
+                mint_config: Pubkey::new_unique(),
+                mint_authority: Pubkey::new_unique(),
+                minter_allowance: Pubkey::new_unique(),
+                allocation_bucket: Pubkey::new_unique(),
+                emission_schedule: Pubkey::new_unique(),
+                learner_token_account: learner.token_account,
+                token_program: spl_token::id(),
+                solearn_token_program: solearn_token::id(),
+                instructions_sysvar: sysvar::instructions::id(),
+                staking_pool,
+                stake_position,
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_learning::instruction::CompleteCourse {
+                score: attestation.score,
+                evidence_hash: attestation.evidence_hash.clone(),
+                nonce: attestation.nonce,
+                course_version: attestation.course_version,
+            }
+            .data(),
+        }
+    }
+
+    /// Complete the course with `attestation` signed by `signer`
+    async fn complete_with(
+        &mut self,
+        learner: &TestLearner,
+        attestation: &CompletionAttestation,
+        signer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let message = attestation.try_to_vec().unwrap();
+        let signature = signer.sign_message(&message);
+        let instructions = [
+            ed25519_instruction(&signer.pubkey(), &signature, &message),
+            self.complete_course(learner, attestation),
+        ];
+        let wallet = learner.wallet.insecure_clone();
+        self.process(&instructions, &wallet, &[]).await
+    }
+
+    /// Complete the course with an assessor attestation, boosted by the
+    /// learner's stake in `staking_pool`
+    async fn complete_staked(
+        &mut self,
+        learner: &TestLearner,
+        attestation: &CompletionAttestation,
+        staking_pool: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let message = attestation.try_to_vec().unwrap();
+        let signature = self.assessor.sign_message(&message);
+        let instructions = [
+            ed25519_instruction(&self.assessor.pubkey(), &signature, &message),
+            self.complete_course_staked(learner, attestation, Some(staking_pool)),
+        ];
+        let wallet = learner.wallet.insecure_clone();
+        self.process(&instructions, &wallet, &[]).await
+    }
+
+    /// Create the solearn_staking pool of the tenant's mint as its mint
+    /// authority
+    async fn initialize_staking_pool(&mut self) -> Pubkey {
+        let admin = self.admin.insecure_clone();
+        let pool = staking_pool(&self.mint);
+        let initialize = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::InitializePool {
+                staking_pool: pool,
+                vault: Pubkey::find_program_address(&[b"staking-vault", pool.as_ref()], &solearn_staking::id()).0,
+                reward_vault: Pubkey::find_program_address(
+                    &[b"staking-rewards", pool.as_ref()],
+                    &solearn_staking::id(),
+                )
+                .0,
+                mint: self.mint,
+                mint_config: None,
+                authority: admin.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+                rent: sysvar::rent::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::InitializePool {}.data(),
+        };
+        self.process(&[initialize], &admin, &[]).await.unwrap();
+        pool
+    }
+
+    /// Mint `amount` to the learner and stake it in `pool` with the shortest
+    /// lockup
+    async fn stake(&mut self, learner: &TestLearner, pool: Pubkey, amount: u64) {
+        let admin = self.admin.insecure_clone();
+        let mint_to = spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &self.mint,
+            &learner.token_account,
+            &admin.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap();
+        self.process(&[mint_to], &admin, &[]).await.unwrap();
+
+        let wallet = learner.wallet.insecure_clone();
+        let stake = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::Stake {
+                staking_pool: pool,
+                stake_position: stake_position(&pool, &wallet.pubkey()),
+                vault: Pubkey::find_program_address(&[b"staking-vault", pool.as_ref()], &solearn_staking::id()).0,
+                owner_token_account: learner.token_account,
+                owner: wallet.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::Stake {
+                amount,
+                lockup_option: 0,
+            }
+            .data(),
+        };
+        self.process(&[stake], &wallet, &[]).await.unwrap();
+    }
+
+    fn set_staking_pool(&self, staking_pool: Option<Pubkey>, authority: &Pubkey) -> Instruction {
+        Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::SetStakingPool {
+                program_state: self.program_state(),
+                staking_pool,
+                authority: *authority,
+            }
+            .to_account_metas(None),
+            data: solearn_learning::instruction::SetStakingPool {}.data(),
+        }
+    }
+
+    /// Publish `content(version)` as the next course version and approve the
+    /// resulting review round
+    async fn publish_and_approve(&mut self, version: u32) {
+        let admin = self.admin.insecure_clone();
+        let update = Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::UpdateCourse {
+                program_state: self.program_state(),
+                course: self.course(),
+                institution: self.institution(),
+                course_version: Some(self.course_version(version)),
+                authority: admin.pubkey(),
+                system_program: Some(system_program::id()),
+            }
+            .to_account_metas(None),
+            data: solearn_learning::instruction::UpdateCourse {
+                title: None,
+                description: None,
+                reward_amount: None,
+                required_score: None,
+                active: None,
+                content: Some(content(version as u8)),
+                accept_previous_versions: None,
+            }
+            .data(),
+        };
+        let review_round = version - 1;
+        let review = Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::ReviewCourse {
+                program_state: self.program_state(),
+                course: self.course(),
+                institution: self.institution(),
+                course_review: Pubkey::find_program_address(
+                    &[
+                        b"course_review",
+                        self.course().as_ref(),
+                        admin.pubkey().as_ref(),
+                        &review_round.to_le_bytes(),
+                    ],
+                    &solearn_learning::id(),
+                )
+                .0,
+                reviewer: admin.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_learning::instruction::ReviewCourse { approve: true }.data(),
+        };
+        self.process(&[update, review], &admin, &[]).await.unwrap();
+    }
+
+    async fn set_accept_previous_versions(&mut self, accept: bool) {
+        let admin = self.admin.insecure_clone();
+        let update = Instruction {
+            program_id: solearn_learning::id(),
+            accounts: solearn_learning::accounts::UpdateCourse {
+                program_state: self.program_state(),
+                course: self.course(),
+                institution: self.institution(),
+                course_version: None,
+                authority: admin.pubkey(),
+                system_program: None,
+            }
+            .to_account_metas(None),
+            data: solearn_learning::instruction::UpdateCourse {
+                title: None,
+                description: None,
+                reward_amount: None,
+                required_score: None,
+                active: None,
+                content: None,
+                accept_previous_versions: Some(accept),
+            }
+            .data(),
+        };
+        self.process(&[update], &admin, &[]).await.unwrap();
+    }
+}
+
+/// Start a validator with an initialized tenant that has a LEARN mint, a
+/// registered assessor and an approved course at version 1
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_learning",
+        solearn_learning::id(),
+        processor!(solearn_learning::entry),
+    );
+    program_test.add_program(
+        "solearn_token",
+        solearn_token::id(),
+        processor!(solearn_token::entry),
+    );
+    program_test.add_program(
+        "solearn_staking",
+        solearn_staking::id(),
+        processor!(solearn_staking::entry),
+    );
+
+    let admin = Keypair::new();
+    program_test.add_account(admin.pubkey(), funded_account());
+
+    let (banks_client, _payer, _recent_blockhash) = program_test.start().await;
+    let mint = Keypair::new();
+    let mut fixture = Fixture {
+        banks_client,
+        tenant: Keypair::new(),
+        admin: admin.insecure_clone(),
+        assessor: Keypair::new(),
+        mint: mint.pubkey(),
+    };
+
+    let rent = fixture.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &admin.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &admin.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    fixture.process(&create_mint, &admin, &[&mint]).await.unwrap();
+
+    let initialize = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::InitializeProgram {
+            program_state: fixture.program_state(),
+            tenant: fixture.tenant.pubkey(),
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::InitializeProgram {
+            token_mint: mint.pubkey(),
+            token_program: spl_token::id(),
+        }
+        .data(),
+    };
+    let tenant = fixture.tenant.insecure_clone();
+    fixture.process(&[initialize], &admin, &[&tenant]).await.unwrap();
+
+    let pause_minting = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::UpdateProgramConfig {
+            program_state: fixture.program_state(),
+            authority: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::UpdateProgramConfig {
+            pause_program: None,
+            paused_operations: Some(operation::REWARD_MINTING),
+        }
+        .data(),
+    };
+    let create_institution = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::CreateInstitution {
+            program_state: fixture.program_state(),
+            institution: fixture.institution(),
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::CreateInstitution {
+            institution_id: INSTITUTION_ID.to_string(),
+            name: "Partner University".to_string(),
+            metadata_uri: String::new(),
+        }
+        .data(),
+    };
+    let register_course = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::RegisterCourse {
+            program_state: fixture.program_state(),
+            institution: fixture.institution(),
+            course: fixture.course(),
+            course_version: fixture.course_version(1),
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::RegisterCourse {
+            course_id: COURSE_ID.to_string(),
+            title: "Intro to Solana".to_string(),
+            description: String::new(),
+            reward_amount: REWARD_AMOUNT,
+            required_score: REQUIRED_SCORE,
+            content: content(1),
+        }
+        .data(),
+    };
+    let instructions = [
+        pause_minting,
+        fixture.grant_role(Role::CourseRegistrar, admin.pubkey()),
+        fixture.grant_role(Role::Reviewer, admin.pubkey()),
+        fixture.grant_role(Role::Assessor, fixture.assessor.pubkey()),
+        create_institution,
+        register_course,
+    ];
+    fixture.process(&instructions, &admin, &[]).await.unwrap();
+
+    fixture
+}
+
+#[tokio::test]
+async fn assessor_attestation_records_the_completion() {
+    let mut fixture = setup().await;
+    let learner = fixture.new_learner().await;
+    let assessor = fixture.assessor.insecure_clone();
+
+    // A score below the course's requirement fails even when attested
+    let failing = fixture.attestation(&learner, 1, REQUIRED_SCORE - 1);
+    assert!(fixture.complete_with(&learner, &failing, &assessor).await.is_err());
+
+    // So does an evidence hash longer than the completion can store
+    let mut overlong = fixture.attestation(&learner, 1, 90);
+    overlong.evidence_hash = "b".repeat(65);
+    assert!(fixture.complete_with(&learner, &overlong, &assessor).await.is_err());
+
+    let attestation = fixture.attestation(&learner, 1, 90);
+    fixture
+        .complete_with(&learner, &attestation, &assessor)
+        .await
+        .unwrap();
+
+    // The completion records the assessor and its reward is left to claim
+    let wallet = learner.wallet.pubkey();
+    let completion = fixture.course_completion(&wallet).await;
+    assert!(completion.completed);
+    assert_eq!(completion.score, 90);
+    assert_eq!(completion.course_version, 1);
+    assert_eq!(completion.evidence_hash, EVIDENCE_HASH);
+    assert_eq!(completion.assessor, assessor.pubkey());
+    assert_eq!(completion.xp_earned, 900);
+    assert_eq!(completion.tokens_earned, REWARD_AMOUNT);
+    assert!(!completion.reward_minted);
+
+    // The same course cannot be completed twice
+    let mut again = fixture.attestation(&learner, 1, 95);
+    again.nonce = 2;
+    assert!(fixture.complete_with(&learner, &again, &assessor).await.is_err());
+}
+
+#[tokio::test]
+async fn completion_rejects_invalid_attestations() {
+    let mut fixture = setup().await;
+    let learner = fixture.new_learner().await;
+    let assessor = fixture.assessor.insecure_clone();
+    let attestation = fixture.attestation(&learner, 1, 90);
+    let message = attestation.try_to_vec().unwrap();
+
+    // Without the ed25519 instruction there is nothing to verify
+    let complete = fixture.complete_course(&learner, &attestation);
+    let wallet = learner.wallet.insecure_clone();
+    assert!(fixture.process(&[complete], &wallet, &[]).await.is_err());
+
+    // A valid signature from a key that is not a registered assessor
+    let outsider = Keypair::new();
+    assert!(fixture.complete_with(&learner, &attestation, &outsider).await.is_err());
+
+    // The assessor's key with a signature made by another key fails in the
+    // ed25519 program
+    let forged = ed25519_instruction(&assessor.pubkey(), &outsider.sign_message(&message), &message);
+    let complete = fixture.complete_course(&learner, &attestation);
+    assert!(fixture.process(&[forged, complete], &wallet, &[]).await.is_err());
+
+    // The assessor signed a different score than the one submitted
+    let signed = fixture.attestation(&learner, 1, 75);
+    let signed_message = signed.try_to_vec().unwrap();
+    let signature = assessor.sign_message(&signed_message);
+    let instructions = [
+        ed25519_instruction(&assessor.pubkey(), &signature, &signed_message),
+        fixture.complete_course(&learner, &attestation),
+    ];
+    assert!(fixture.process(&instructions, &wallet, &[]).await.is_err());
+
+    // An attestation for another learner cannot be replayed
+    let other = fixture.new_learner().await;
+    let for_other = fixture.attestation(&other, 1, 90);
+    let other_message = for_other.try_to_vec().unwrap();
+    let instructions = [
+        ed25519_instruction(&assessor.pubkey(), &assessor.sign_message(&other_message), &other_message),
+        fixture.complete_course(&learner, &attestation),
+    ];
+    assert!(fixture.process(&instructions, &wallet, &[]).await.is_err());
+
+    // The untouched attestation still goes through
+    fixture
+        .complete_with(&learner, &attestation, &assessor)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn previous_versions_count_only_while_accepted() {
+    let mut fixture = setup().await;
+    let assessor = fixture.assessor.insecure_clone();
+    fixture.publish_and_approve(2).await;
+
+    // Version 1 still counts by default, an unpublished version never does
+    let early = fixture.new_learner().await;
+    let unpublished = fixture.attestation(&early, 3, 90);
+    assert!(fixture.complete_with(&early, &unpublished, &assessor).await.is_err());
+    let attestation = fixture.attestation(&early, 1, 90);
+    fixture.complete_with(&early, &attestation, &assessor).await.unwrap();
+    let wallet = early.wallet.pubkey();
+    assert_eq!(fixture.course_completion(&wallet).await.course_version, 1);
+
+    // Once the course stops accepting them only the current version counts
+    fixture.set_accept_previous_versions(false).await;
+    let late = fixture.new_learner().await;
+    let outdated = fixture.attestation(&late, 1, 90);
+    assert!(fixture.complete_with(&late, &outdated, &assessor).await.is_err());
+    let current = fixture.attestation(&late, 2, 90);
+    fixture.complete_with(&late, &current, &assessor).await.unwrap();
+    let wallet = late.wallet.pubkey();
+    assert_eq!(fixture.course_completion(&wallet).await.course_version, 2);
+}
+
+#[tokio::test]
+async fn staking_boost_applies_only_from_the_trusted_pool() {
+    let mut fixture = setup().await;
+    let admin = fixture.admin.insecure_clone();
+    let assessor = fixture.assessor.insecure_clone();
+    let pool = fixture.initialize_staking_pool().await;
+    // 1,000 LEARN reaches the Bronze tier and its 5% boost
+    let bronze = 1_000 * 10u64.pow(6);
+
+    // The pool is not trusted until the admin pins it
+    let learner = fixture.new_learner().await;
+    fixture.stake(&learner, pool, bronze).await;
+    let attestation = fixture.attestation(&learner, 1, 90);
+    assert!(fixture.complete_staked(&learner, &attestation, pool).await.is_err());
+
+    // Only the admin pins the pool
+    let outsider = learner.wallet.insecure_clone();
+    let pin = fixture.set_staking_pool(Some(pool), &outsider.pubkey());
+    assert!(fixture.process(&[pin], &outsider, &[]).await.is_err());
+    let pin = fixture.set_staking_pool(Some(pool), &admin.pubkey());
+    fixture.process(&[pin], &admin, &[]).await.unwrap();
+
+    fixture
+        .complete_staked(&learner, &attestation, pool)
+        .await
+        .unwrap();
+    let wallet = learner.wallet.pubkey();
+    assert_eq!(fixture.course_completion(&wallet).await.tokens_earned, 105);
+
+    // Once unpinned, a stake no longer boosts rewards
+    let unpin = fixture.set_staking_pool(None, &admin.pubkey());
+    fixture.process(&[unpin], &admin, &[]).await.unwrap();
+    let late = fixture.new_learner().await;
+    fixture.stake(&late, pool, bronze).await;
+    let attestation = fixture.attestation(&late, 1, 90);
+    assert!(fixture.complete_staked(&late, &attestation, pool).await.is_err());
+    fixture.complete_with(&late, &attestation, &assessor).await.unwrap();
+    let wallet = late.wallet.pubkey();
+    assert_eq!(fixture.course_completion(&wallet).await.tokens_earned, REWARD_AMOUNT);
+}
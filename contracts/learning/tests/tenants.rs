@@ -0,0 +1,444 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_program_test::{processor, tokio, BanksClient, BanksClientError, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_learning::{CourseContent, ProgramState, Role};
+
+/// A platform instance: its tenant key, admin and reward mint
+struct Tenant {
+    key: Keypair,
+    admin: Keypair,
+    token_mint: Pubkey,
+}
+
+impl Tenant {
+    fn new() -> Self {
+        Self {
+            key: Keypair::new(),
+            admin: Keypair::new(),
+            token_mint: Pubkey::new_unique(),
+        }
+    }
+
+    fn program_state(&self) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"program_state", self.key.pubkey().as_ref()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn learner(&self, wallet: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"learner", self.program_state().as_ref(), wallet.as_ref()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+
+    fn institution(&self, institution_id: &str) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"institution", self.program_state().as_ref(), institution_id.as_bytes()],
+            &solearn_learning::id(),
+        )
+        .0
+    }
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+fn course_address(institution: &Pubkey, course_id: &str) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"course", institution.as_ref(), course_id.as_bytes()],
+        &solearn_learning::id(),
+    )
+    .0
+}
+
+fn course_version_address(course: &Pubkey, version: u32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"course_version", course.as_ref(), &version.to_le_bytes()],
+        &solearn_learning::id(),
+    )
+    .0
+}
+
+async fn process(
+    banks_client: &mut BanksClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+) -> Result<(), BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        &[payer],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await
+}
+
+async fn program_state(banks_client: &mut BanksClient, tenant: &Tenant) -> ProgramState {
+    let account = banks_client
+        .get_account(tenant.program_state())
+        .await
+        .unwrap()
+        .unwrap();
+    ProgramState::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+fn initialize_program(tenant: &Tenant) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::InitializeProgram {
+            program_state: tenant.program_state(),
+            tenant: tenant.key.pubkey(),
+            authority: tenant.admin.pubkey(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::InitializeProgram {
+            token_mint: tenant.token_mint,
+            token_program: anchor_spl::token::ID,
+        }
+        .data(),
+    }
+}
+
+/// Initialize the program state of `tenant`, signed by its admin and key
+async fn initialize(banks_client: &mut BanksClient, tenant: &Tenant) -> Result<(), BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let transaction = Transaction::new_signed_with_payer(
+        &[initialize_program(tenant)],
+        Some(&tenant.admin.pubkey()),
+        &[&tenant.admin, &tenant.key],
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await
+}
+
+fn register_learner(tenant: &Tenant, wallet: &Keypair) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::RegisterLearner {
+            program_state: tenant.program_state(),
+            learner: tenant.learner(&wallet.pubkey()),
+            wallet: wallet.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::RegisterLearner {
+            learner_name: "Ada".to_string(),
+        }
+        .data(),
+    }
+}
+
+fn pause_program(tenant: &Tenant) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::UpdateProgramConfig {
+            program_state: tenant.program_state(),
+            authority: tenant.admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::UpdateProgramConfig {
+            pause_program: Some(true),
+            paused_operations: None,
+        }
+        .data(),
+    }
+}
+
+fn set_treasurer(tenant: &Tenant, key: Pubkey, grant: bool) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::AdminAction {
+            program_state: tenant.program_state(),
+            authority: tenant.admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: if grant {
+            solearn_learning::instruction::GrantRole { role: Role::Treasurer, key }.data()
+        } else {
+            solearn_learning::instruction::RevokeRole { role: Role::Treasurer, key }.data()
+        },
+    }
+}
+
+fn grant_registrar(tenant: &Tenant, key: Pubkey) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::AdminAction {
+            program_state: tenant.program_state(),
+            authority: tenant.admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::GrantRole {
+            role: Role::CourseRegistrar,
+            key,
+        }
+        .data(),
+    }
+}
+
+fn update_bond_config(tenant: &Tenant, treasurer: &Keypair, course_bond: u64) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::TreasurerAction {
+            program_state: tenant.program_state(),
+            treasurer: treasurer.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::UpdateBondConfig {
+            course_bond: Some(course_bond),
+            rejection_penalty_bps: Some(2_500),
+            bond_treasury: Some(tenant.token_mint),
+        }
+        .data(),
+    }
+}
+
+fn create_institution(tenant: &Tenant, authority: &Keypair, institution_id: &str) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::CreateInstitution {
+            program_state: tenant.program_state(),
+            institution: tenant.institution(institution_id),
+            authority: authority.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::CreateInstitution {
+            institution_id: institution_id.to_string(),
+            name: "Partner University".to_string(),
+            metadata_uri: String::new(),
+        }
+        .data(),
+    }
+}
+
+/// Register `course_id` in `institution` through the program state of
+/// `tenant`
+fn register_course(
+    tenant: &Tenant,
+    institution: Pubkey,
+    registrar: &Keypair,
+    course_id: &str,
+) -> Instruction {
+    Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::RegisterCourse {
+            program_state: tenant.program_state(),
+            institution,
+            course: course_address(&institution, course_id),
+            course_version: course_version_address(&course_address(&institution, course_id), 1),
+            authority: registrar.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::RegisterCourse {
+            course_id: course_id.to_string(),
+            title: "Intro to Solana".to_string(),
+            description: String::new(),
+            reward_amount: 100,
+            required_score: 70,
+            content: CourseContent {
+                content_hash: [7; 32],
+                content_uri: "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_string(),
+            },
+        }
+        .data(),
+    }
+}
+
+/// Start a validator with two initialized tenants and a funded wallet
+async fn setup() -> (BanksClient, Tenant, Tenant, Keypair) {
+    let mut program_test = ProgramTest::new(
+        "solearn_learning",
+        solearn_learning::id(),
+        processor!(solearn_learning::entry),
+    );
+
+    let tenant_a = Tenant::new();
+    let tenant_b = Tenant::new();
+    let wallet = Keypair::new();
+    for key in [tenant_a.admin.pubkey(), tenant_b.admin.pubkey(), wallet.pubkey()] {
+        program_test.add_account(key, funded_account());
+    }
+
+    let (mut banks_client, _payer, _recent_blockhash) = program_test.start().await;
+    initialize(&mut banks_client, &tenant_a).await.unwrap();
+    initialize(&mut banks_client, &tenant_b).await.unwrap();
+
+    (banks_client, tenant_a, tenant_b, wallet)
+}
+
+#[tokio::test]
+async fn tenants_keep_separate_state_and_learners() {
+    let (mut banks_client, tenant_a, tenant_b, wallet) = setup().await;
+
+    // A tenant's program state can only be created once
+    assert!(initialize(&mut banks_client, &tenant_a).await.is_err());
+
+    // Nobody can claim a tenant key without its signature
+    let squatted = Tenant {
+        admin: wallet.insecure_clone(),
+        ..Tenant::new()
+    };
+    let mut squat = initialize_program(&squatted);
+    squat.accounts[1].is_signer = false;
+    assert!(process(&mut banks_client, &[squat], &wallet).await.is_err());
+
+    // The same wallet is a separate learner in each tenant
+    assert_ne!(tenant_a.learner(&wallet.pubkey()), tenant_b.learner(&wallet.pubkey()));
+    process(&mut banks_client, &[register_learner(&tenant_a, &wallet)], &wallet)
+        .await
+        .unwrap();
+    process(&mut banks_client, &[register_learner(&tenant_b, &wallet)], &wallet)
+        .await
+        .unwrap();
+    assert!(process(&mut banks_client, &[register_learner(&tenant_a, &wallet)], &wallet)
+        .await
+        .is_err());
+
+    let state_a = program_state(&mut banks_client, &tenant_a).await;
+    let state_b = program_state(&mut banks_client, &tenant_b).await;
+    assert_eq!(state_a.tenant, tenant_a.key.pubkey());
+    assert_eq!(state_a.token_mint, tenant_a.token_mint);
+    assert_eq!(state_b.token_mint, tenant_b.token_mint);
+    assert_eq!(state_a.total_learners, 1);
+    assert_eq!(state_b.total_learners, 1);
+}
+
+#[tokio::test]
+async fn pause_roles_and_courses_are_per_tenant() {
+    let (mut banks_client, tenant_a, tenant_b, registrar) = setup().await;
+
+    // The same institution ID exists independently in both tenants
+    process(
+        &mut banks_client,
+        &[create_institution(&tenant_a, &registrar, "UNI")],
+        &registrar,
+    )
+    .await
+    .unwrap();
+    process(
+        &mut banks_client,
+        &[create_institution(&tenant_b, &registrar, "UNI")],
+        &registrar,
+    )
+    .await
+    .unwrap();
+
+    // A role granted by one tenant's admin means nothing in the other
+    let admin_a = tenant_a.admin.insecure_clone();
+    process(&mut banks_client, &[grant_registrar(&tenant_a, registrar.pubkey())], &admin_a)
+        .await
+        .unwrap();
+    let institution_a = tenant_a.institution("UNI");
+    let institution_b = tenant_b.institution("UNI");
+    process(
+        &mut banks_client,
+        &[register_course(&tenant_a, institution_a, &registrar, "INTRO101")],
+        &registrar,
+    )
+    .await
+    .unwrap();
+    assert!(process(
+        &mut banks_client,
+        &[register_course(&tenant_b, institution_b, &registrar, "INTRO101")],
+        &registrar,
+    )
+    .await
+    .is_err());
+
+    // Another tenant's institution cannot be used through this tenant's state
+    let admin_b = tenant_b.admin.insecure_clone();
+    process(&mut banks_client, &[grant_registrar(&tenant_b, registrar.pubkey())], &admin_b)
+        .await
+        .unwrap();
+    assert!(process(
+        &mut banks_client,
+        &[register_course(&tenant_b, institution_a, &registrar, "INTRO102")],
+        &registrar,
+    )
+    .await
+    .is_err());
+    process(
+        &mut banks_client,
+        &[register_course(&tenant_b, institution_b, &registrar, "INTRO101")],
+        &registrar,
+    )
+    .await
+    .unwrap();
+
+    // Pausing one tenant leaves the other running
+    process(&mut banks_client, &[pause_program(&tenant_a)], &admin_a)
+        .await
+        .unwrap();
+    let learner = Keypair::new();
+    let fund = solana_sdk::system_instruction::transfer(
+        &registrar.pubkey(),
+        &learner.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+    process(&mut banks_client, &[fund], &registrar).await.unwrap();
+    assert!(process(&mut banks_client, &[register_learner(&tenant_a, &learner)], &learner)
+        .await
+        .is_err());
+    process(&mut banks_client, &[register_learner(&tenant_b, &learner)], &learner)
+        .await
+        .unwrap();
+
+    let state_a = program_state(&mut banks_client, &tenant_a).await;
+    let state_b = program_state(&mut banks_client, &tenant_b).await;
+    assert!(state_a.paused);
+    assert!(!state_b.paused);
+    assert_eq!(state_a.total_courses, 1);
+    assert_eq!(state_b.total_courses, 1);
+}
+
+#[tokio::test]
+async fn bond_config_requires_the_treasurer_role() {
+    let (mut banks_client, tenant, _tenant_b, treasurer) = setup().await;
+    let admin = tenant.admin.insecure_clone();
+
+    // Being the admin is not enough to set the bond terms
+    assert!(process(&mut banks_client, &[update_bond_config(&tenant, &admin, 500)], &admin)
+        .await
+        .is_err());
+
+    process(&mut banks_client, &[set_treasurer(&tenant, treasurer.pubkey(), true)], &admin)
+        .await
+        .unwrap();
+    process(&mut banks_client, &[update_bond_config(&tenant, &treasurer, 1_000)], &treasurer)
+        .await
+        .unwrap();
+    let state = program_state(&mut banks_client, &tenant).await;
+    assert_eq!(state.course_bond, 1_000);
+    assert_eq!(state.rejection_penalty_bps, 2_500);
+    assert_eq!(state.bond_treasury, tenant.token_mint);
+
+    // A revoked treasurer loses access
+    process(&mut banks_client, &[set_treasurer(&tenant, treasurer.pubkey(), false)], &admin)
+        .await
+        .unwrap();
+    assert!(process(&mut banks_client, &[update_bond_config(&tenant, &treasurer, 2_000)], &treasurer)
+        .await
+        .is_err());
+    assert_eq!(program_state(&mut banks_client, &tenant).await.course_bond, 1_000);
+}
@@ -0,0 +1,436 @@
+use anchor_lang::prelude::*;
+use solana_program::instruction::Instruction;
+use solana_program::program::invoke_signed;
+
+declare_id!("LearnMsigProgram111111111111111111111111111");
+
+/// M-of-N multisig. Each multisig controls a signer PDA,
+/// `[b"multisig-signer", multisig]`, which can be set as the `authority` of
+/// a solearn_token `MintConfig` or the solearn_learning `ProgramState`.
+/// Members propose arbitrary instructions signed by that PDA, such as
+/// `update_mint_config` or `mint_tokens`, and once enough members approve,
+/// anyone can execute them.
+#[program]
+pub mod solearn_multisig {
+    use super::*;
+
+    /// Create a new multisig with the given members and approval threshold
+    pub fn create_multisig(
+        ctx: Context<CreateMultisig>,
+        multisig_id: u64,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_members(&members, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.creator = ctx.accounts.creator.key();
+        multisig.multisig_id = multisig_id;
+        multisig.members = members;
+        multisig.threshold = threshold;
+        multisig.proposal_count = 0;
+        multisig.owner_set_seqno = 0;
+        multisig.bump = *ctx.bumps.get("multisig").unwrap();
+        multisig.signer_bump = *ctx.bumps.get("multisig_signer").unwrap();
+
+        msg!(
+            "Multisig created with {} members and threshold {}",
+            multisig.members.len(),
+            threshold
+        );
+
+        Ok(())
+    }
+
+    /// Propose an instruction to be signed by the multisig signer PDA. The
+    /// proposer's approval is recorded immediately.
+    pub fn create_proposal(
+        ctx: Context<CreateProposal>,
+        program_id: Pubkey,
+        accounts: Vec<ProposalAccount>,
+        data: Vec<u8>,
+    ) -> Result<()> {
+        let multisig = &mut ctx.accounts.multisig;
+        let proposer_index = multisig
+            .member_index(&ctx.accounts.proposer.key())
+            .ok_or(ErrorCode::NotAMember)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.multisig = multisig.key();
+        proposal.proposer = ctx.accounts.proposer.key();
+        proposal.index = multisig.proposal_count;
+        proposal.program_id = program_id;
+        proposal.accounts = accounts;
+        proposal.data = data;
+        proposal.approvals = vec![false; multisig.members.len()];
+        proposal.approvals[proposer_index] = true;
+        proposal.executed = false;
+        proposal.owner_set_seqno = multisig.owner_set_seqno;
+        proposal.bump = *ctx.bumps.get("proposal").unwrap();
+
+        multisig.proposal_count += 1;
+
+        emit!(ProposalCreated {
+            multisig: proposal.multisig,
+            proposal: proposal.key(),
+            proposer: proposal.proposer,
+            program_id,
+        });
+
+        msg!("Proposal {} created", proposal.index);
+
+        Ok(())
+    }
+
+    /// Approve a pending proposal as a member
+    pub fn approve_proposal(ctx: Context<ApproveProposal>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        let member_index = multisig
+            .member_index(&ctx.accounts.member.key())
+            .ok_or(ErrorCode::NotAMember)?;
+        if proposal.owner_set_seqno != multisig.owner_set_seqno {
+            return Err(ErrorCode::ProposalStale.into());
+        }
+        if proposal.executed {
+            return Err(ErrorCode::ProposalAlreadyExecuted.into());
+        }
+
+        proposal.approvals[member_index] = true;
+
+        emit!(ProposalApproved {
+            multisig: multisig.key(),
+            proposal: proposal.key(),
+            member: ctx.accounts.member.key(),
+            approvals: proposal.approval_count(),
+        });
+
+        msg!("Proposal {} approved by {}", proposal.index, ctx.accounts.member.key());
+
+        Ok(())
+    }
+
+    /// Execute a proposal that reached the threshold. Anyone may call this;
+    /// every account the proposed instruction uses, plus its program, must be
+    /// passed as remaining accounts.
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        let multisig = &ctx.accounts.multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        if proposal.executed {
+            return Err(ErrorCode::ProposalAlreadyExecuted.into());
+        }
+        if proposal.owner_set_seqno != multisig.owner_set_seqno {
+            return Err(ErrorCode::ProposalStale.into());
+        }
+        if proposal.approval_count() < multisig.threshold {
+            return Err(ErrorCode::NotEnoughApprovals.into());
+        }
+
+        // Mark executed and write the flag to the account before invoking.
+        // Anchor only serializes accounts on exit, so without this a re-entrant
+        // call from the invoked program would still see the proposal unexecuted.
+        proposal.executed = true;
+        proposal.exit(&crate::ID)?;
+
+        let instruction = Instruction {
+            program_id: proposal.program_id,
+            accounts: proposal.accounts.iter().map(Into::into).collect(),
+            data: proposal.data.clone(),
+        };
+
+        let multisig_key = multisig.key();
+        let seeds = &[
+            b"multisig-signer".as_ref(),
+            multisig_key.as_ref(),
+            &[multisig.signer_bump],
+        ];
+        let signer = &[&seeds[..]];
+
+        invoke_signed(&instruction, ctx.remaining_accounts, signer)?;
+
+        emit!(ProposalExecuted {
+            multisig: multisig_key,
+            proposal: proposal.key(),
+        });
+
+        msg!("Proposal {} executed", proposal.index);
+
+        Ok(())
+    }
+
+    /// Replace the member set and threshold. Only callable by the multisig
+    /// signer itself, i.e. through an executed proposal. Pending proposals
+    /// become stale.
+    pub fn set_members(
+        ctx: Context<SetMembers>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        validate_members(&members, threshold)?;
+
+        let multisig = &mut ctx.accounts.multisig;
+        multisig.members = members;
+        multisig.threshold = threshold;
+        multisig.owner_set_seqno = multisig.owner_set_seqno.checked_add(1).unwrap();
+
+        msg!(
+            "Multisig members updated: {} members, threshold {}",
+            multisig.members.len(),
+            threshold
+        );
+
+        Ok(())
+    }
+}
+
+/// Check that a member list is non-empty, unique, within bounds and can
+/// reach `threshold`
+fn validate_members(members: &[Pubkey], threshold: u8) -> Result<()> {
+    if members.is_empty() || members.len() > Multisig::MAX_MEMBERS {
+        return Err(ErrorCode::InvalidMembers.into());
+    }
+    for (index, member) in members.iter().enumerate() {
+        if members[index + 1..].contains(member) {
+            return Err(ErrorCode::DuplicateMember.into());
+        }
+    }
+    if threshold == 0 || usize::from(threshold) > members.len() {
+        return Err(ErrorCode::InvalidThreshold.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(multisig_id: u64)]
+pub struct CreateMultisig<'info> {
+    #[account(
+        init,
+        seeds = [b"multisig", creator.key().as_ref(), &multisig_id.to_le_bytes()],
+        bump,
+        payer = creator,
+        space = 8 + Multisig::LEN
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"multisig-signer", multisig.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: The PDA that signs executed proposals on behalf of the multisig
+    pub multisig_signer: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub creator: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(program_id: Pubkey, accounts: Vec<ProposalAccount>, data: Vec<u8>)]
+pub struct CreateProposal<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref(), &multisig.multisig_id.to_le_bytes()],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        init,
+        seeds = [b"proposal", multisig.key().as_ref(), &multisig.proposal_count.to_le_bytes()],
+        bump,
+        payer = proposer,
+        space = 8 + Proposal::space(accounts.len(), data.len(), multisig.members.len())
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApproveProposal<'info> {
+    #[account(
+        seeds = [b"multisig", multisig.creator.as_ref(), &multisig.multisig_id.to_le_bytes()],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    pub member: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    #[account(
+        seeds = [b"multisig", multisig.creator.as_ref(), &multisig.multisig_id.to_le_bytes()],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", multisig.key().as_ref(), &proposal.index.to_le_bytes()],
+        bump = proposal.bump,
+        has_one = multisig,
+    )]
+    pub proposal: Account<'info, Proposal>,
+}
+
+#[derive(Accounts)]
+pub struct SetMembers<'info> {
+    #[account(
+        mut,
+        seeds = [b"multisig", multisig.creator.as_ref(), &multisig.multisig_id.to_le_bytes()],
+        bump = multisig.bump,
+    )]
+    pub multisig: Account<'info, Multisig>,
+
+    #[account(
+        seeds = [b"multisig-signer", multisig.key().as_ref()],
+        bump = multisig.signer_bump,
+    )]
+    pub multisig_signer: Signer<'info>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct Multisig {
+    pub creator: Pubkey,               // 32 bytes
+    pub multisig_id: u64,              // 8 bytes
+    pub members: Vec<Pubkey>,          // 4 + 32 * MAX_MEMBERS bytes
+    pub threshold: u8,                 // 1 byte
+    pub proposal_count: u64,           // 8 bytes
+    pub owner_set_seqno: u32,          // 4 bytes
+    pub bump: u8,                      // 1 byte
+    pub signer_bump: u8,               // 1 byte
+}
+
+impl Multisig {
+    pub const MAX_MEMBERS: usize = 10;
+    pub const LEN: usize = 32 + 8 + (4 + 32 * Self::MAX_MEMBERS) + 1 + 8 + 4 + 1 + 1;
+
+    pub fn member_index(&self, key: &Pubkey) -> Option<usize> {
+        self.members.iter().position(|member| member == key)
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Proposal {
+    pub multisig: Pubkey,              // 32 bytes
+    pub proposer: Pubkey,              // 32 bytes
+    pub index: u64,                    // 8 bytes
+    pub program_id: Pubkey,            // 32 bytes
+    pub accounts: Vec<ProposalAccount>, // 4 + 34 * accounts bytes
+    pub data: Vec<u8>,                 // 4 + data bytes
+    pub approvals: Vec<bool>,          // 4 + members bytes
+    pub executed: bool,                // 1 byte
+    pub owner_set_seqno: u32,          // 4 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl Proposal {
+    pub fn space(accounts: usize, data: usize, members: usize) -> usize {
+        32 + 32 + 8 + 32
+            + (4 + ProposalAccount::LEN * accounts)
+            + (4 + data)
+            + (4 + members)
+            + 1 + 4 + 1
+    }
+
+    pub fn approval_count(&self) -> u8 {
+        // Bounded by Multisig::MAX_MEMBERS
+        self.approvals.iter().filter(|approved| **approved).count() as u8
+    }
+}
+
+/// Account meta of a proposed instruction
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, Default)]
+pub struct ProposalAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl ProposalAccount {
+    pub const LEN: usize = 32 + 1 + 1;
+}
+
+impl From<&ProposalAccount> for AccountMeta {
+    fn from(account: &ProposalAccount) -> Self {
+        AccountMeta {
+            pubkey: account.pubkey,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        }
+    }
+}
+
+impl From<&AccountMeta> for ProposalAccount {
+    fn from(meta: &AccountMeta) -> Self {
+        ProposalAccount {
+            pubkey: meta.pubkey,
+            is_signer: meta.is_signer,
+            is_writable: meta.is_writable,
+        }
+    }
+}
+
+#[event]
+pub struct ProposalCreated {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub proposer: Pubkey,
+    pub program_id: Pubkey,
+}
+
+#[event]
+pub struct ProposalApproved {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+    pub member: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct ProposalExecuted {
+    pub multisig: Pubkey,
+    pub proposal: Pubkey,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Member list is empty or too long")]
+    InvalidMembers,
+
+    #[msg("Member list contains duplicates")]
+    DuplicateMember,
+
+    #[msg("Threshold must be between 1 and the number of members")]
+    InvalidThreshold,
+
+    #[msg("Signer is not a member of this multisig")]
+    NotAMember,
+
+    #[msg("Proposal was created for a previous member set")]
+    ProposalStale,
+
+    #[msg("Proposal has already been executed")]
+    ProposalAlreadyExecuted,
+
+    #[msg("Proposal does not have enough approvals")]
+    NotEnoughApprovals,
+}
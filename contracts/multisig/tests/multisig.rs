@@ -0,0 +1,469 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClient, ProgramTest};
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    native_token::LAMPORTS_PER_SOL,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_multisig::ProposalAccount;
+
+const MULTISIG_ID: u64 = 7;
+
+struct Fixture {
+    banks_client: BanksClient,
+    members: Vec<Keypair>,
+    multisig: Pubkey,
+    multisig_signer: Pubkey,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+async fn process(
+    banks_client: &mut BanksClient,
+    instructions: &[Instruction],
+    payer: &Keypair,
+    signers: &[&Keypair],
+) -> Result<(), solana_program_test::BanksClientError> {
+    let recent_blockhash = banks_client.get_latest_blockhash().await.unwrap();
+    let mut all_signers = vec![payer];
+    all_signers.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        instructions,
+        Some(&payer.pubkey()),
+        all_signers.as_slice(),
+        recent_blockhash,
+    );
+    banks_client.process_transaction(transaction).await
+}
+
+async fn account<T: AccountDeserialize>(banks_client: &mut BanksClient, address: Pubkey) -> T {
+    let account = banks_client.get_account(address).await.unwrap().unwrap();
+    T::try_deserialize(&mut account.data.as_slice()).unwrap()
+}
+
+/// Start a validator with a 2-of-3 multisig whose signer PDA holds SOL
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_multisig",
+        solearn_multisig::id(),
+        processor!(solearn_multisig::entry),
+    );
+    program_test.add_program(
+        "solearn_token",
+        solearn_token::id(),
+        processor!(solearn_token::entry),
+    );
+    program_test.add_program(
+        "solearn_learning",
+        solearn_learning::id(),
+        processor!(solearn_learning::entry),
+    );
+
+    let members: Vec<Keypair> = (0..3).map(|_| Keypair::new()).collect();
+    for member in &members {
+        program_test.add_account(member.pubkey(), funded_account());
+    }
+
+    let creator = &members[0];
+    let (multisig, _) = Pubkey::find_program_address(
+        &[b"multisig", creator.pubkey().as_ref(), &MULTISIG_ID.to_le_bytes()],
+        &solearn_multisig::id(),
+    );
+    let (multisig_signer, _) = Pubkey::find_program_address(
+        &[b"multisig-signer", multisig.as_ref()],
+        &solearn_multisig::id(),
+    );
+    program_test.add_account(multisig_signer, funded_account());
+
+    let (mut banks_client, _payer, _recent_blockhash) = program_test.start().await;
+
+    let create_multisig = Instruction {
+        program_id: solearn_multisig::id(),
+        accounts: solearn_multisig::accounts::CreateMultisig {
+            multisig,
+            multisig_signer,
+            creator: creator.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_multisig::instruction::CreateMultisig {
+            multisig_id: MULTISIG_ID,
+            members: members.iter().map(|member| member.pubkey()).collect(),
+            threshold: 2,
+        }
+        .data(),
+    };
+    process(&mut banks_client, &[create_multisig], creator, &[])
+        .await
+        .unwrap();
+
+    Fixture {
+        banks_client,
+        members,
+        multisig,
+        multisig_signer,
+    }
+}
+
+fn proposal_address(multisig: &Pubkey, index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"proposal", multisig.as_ref(), &index.to_le_bytes()],
+        &solearn_multisig::id(),
+    )
+    .0
+}
+
+/// Propose that the multisig signer executes `instruction`
+fn create_proposal(
+    fixture: &Fixture,
+    proposer: &Keypair,
+    index: u64,
+    instruction: &Instruction,
+) -> Instruction {
+    Instruction {
+        program_id: solearn_multisig::id(),
+        accounts: solearn_multisig::accounts::CreateProposal {
+            multisig: fixture.multisig,
+            proposal: proposal_address(&fixture.multisig, index),
+            proposer: proposer.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_multisig::instruction::CreateProposal {
+            program_id: instruction.program_id,
+            accounts: instruction.accounts.iter().map(ProposalAccount::from).collect(),
+            data: instruction.data.clone(),
+        }
+        .data(),
+    }
+}
+
+fn approve_proposal(fixture: &Fixture, member: &Keypair, index: u64) -> Instruction {
+    Instruction {
+        program_id: solearn_multisig::id(),
+        accounts: solearn_multisig::accounts::ApproveProposal {
+            multisig: fixture.multisig,
+            proposal: proposal_address(&fixture.multisig, index),
+            member: member.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_multisig::instruction::ApproveProposal {}.data(),
+    }
+}
+
+/// Execute the proposal at `index`, passing the accounts of the proposed
+/// `instruction` and its program
+fn execute_proposal(fixture: &Fixture, index: u64, instruction: &Instruction) -> Instruction {
+    let mut accounts = solearn_multisig::accounts::ExecuteProposal {
+        multisig: fixture.multisig,
+        proposal: proposal_address(&fixture.multisig, index),
+    }
+    .to_account_metas(None);
+    // The signer PDA is signed for by the program, not the transaction
+    accounts.extend(instruction.accounts.iter().map(|meta| AccountMeta {
+        is_signer: false,
+        ..meta.clone()
+    }));
+    accounts.push(AccountMeta::new_readonly(instruction.program_id, false));
+
+    Instruction {
+        program_id: solearn_multisig::id(),
+        accounts,
+        data: solearn_multisig::instruction::ExecuteProposal {}.data(),
+    }
+}
+
+/// Propose `instruction`, approve it with the second member and execute it
+async fn propose_and_execute(fixture: &mut Fixture, index: u64, instruction: &Instruction) {
+    let proposer = fixture.members[0].insecure_clone();
+    let approver = fixture.members[1].insecure_clone();
+    let create = create_proposal(fixture, &proposer, index, instruction);
+    let approve = approve_proposal(fixture, &approver, index);
+    let execute = execute_proposal(fixture, index, instruction);
+    process(&mut fixture.banks_client, &[create, approve], &proposer, &[&approver])
+        .await
+        .unwrap();
+    process(&mut fixture.banks_client, &[execute], &proposer, &[])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn proposal_executes_once_threshold_is_met() {
+    let mut fixture = setup().await;
+    let recipient = Pubkey::new_unique();
+
+    let transfer = system_instruction::transfer(&fixture.multisig_signer, &recipient, LAMPORTS_PER_SOL);
+    let create = create_proposal(&fixture, &fixture.members[0], 0, &transfer);
+    process(&mut fixture.banks_client, &[create], &fixture.members[0], &[])
+        .await
+        .unwrap();
+
+    // One approval out of two required
+    let execute = execute_proposal(&fixture, 0, &transfer);
+    assert!(process(&mut fixture.banks_client, &[execute], &fixture.members[2], &[])
+        .await
+        .is_err());
+
+    let approve = approve_proposal(&fixture, &fixture.members[1], 0);
+    process(&mut fixture.banks_client, &[approve], &fixture.members[1], &[])
+        .await
+        .unwrap();
+
+    // Anyone can execute once the threshold is met
+    let executor = Keypair::new();
+    let fund_executor = system_instruction::transfer(
+        &fixture.members[2].pubkey(),
+        &executor.pubkey(),
+        LAMPORTS_PER_SOL,
+    );
+    process(&mut fixture.banks_client, &[fund_executor], &fixture.members[2], &[])
+        .await
+        .unwrap();
+    let execute = execute_proposal(&fixture, 0, &transfer);
+    process(&mut fixture.banks_client, &[execute], &executor, &[])
+        .await
+        .unwrap();
+
+    let recipient_account = fixture
+        .banks_client
+        .get_account(recipient)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(recipient_account.lamports, LAMPORTS_PER_SOL);
+
+    // A proposal cannot be executed twice
+    let execute = execute_proposal(&fixture, 0, &transfer);
+    assert!(process(&mut fixture.banks_client, &[execute], &fixture.members[1], &[])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn non_member_cannot_approve() {
+    let mut fixture = setup().await;
+    let recipient = Pubkey::new_unique();
+
+    let transfer = system_instruction::transfer(&fixture.multisig_signer, &recipient, LAMPORTS_PER_SOL);
+    let create = create_proposal(&fixture, &fixture.members[0], 0, &transfer);
+    process(&mut fixture.banks_client, &[create], &fixture.members[0], &[])
+        .await
+        .unwrap();
+
+    let outsider = Keypair::new();
+    let approve = approve_proposal(&fixture, &outsider, 0);
+    assert!(process(&mut fixture.banks_client, &[approve], &fixture.members[1], &[&outsider])
+        .await
+        .is_err());
+
+    let execute = execute_proposal(&fixture, 0, &transfer);
+    assert!(process(&mut fixture.banks_client, &[execute], &fixture.members[2], &[])
+        .await
+        .is_err());
+}
+
+#[tokio::test]
+async fn multisig_signer_updates_token_mint_config() {
+    let mut fixture = setup().await;
+    let admin = fixture.members[0].insecure_clone();
+    let multisig_signer = fixture.multisig_signer;
+
+    let mint = Keypair::new();
+    let (mint_config, _) = Pubkey::find_program_address(
+        &[b"mint-config", mint.pubkey().as_ref()],
+        &solearn_token::id(),
+    );
+    let (mint_authority, _) = Pubkey::find_program_address(
+        &[b"mint-authority", mint.pubkey().as_ref()],
+        &solearn_token::id(),
+    );
+    let update_mint_config = |authority: Pubkey| Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::UpdateMintConfig {
+            mint_config,
+            mint: mint.pubkey(),
+            authority,
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::UpdateMintConfig {
+            new_supply_cap: Some(2_000_000),
+            new_rate_limit: None,
+            pause_minting: Some(true),
+            new_compliance_authority: None,
+        }
+        .data(),
+    };
+
+    // A member creates the mint and hands its authority to the multisig
+    let initialize_mint = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::InitializeMint {
+            mint_config,
+            mint: mint.pubkey(),
+            mint_authority,
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::InitializeMint {
+            decimals: 9,
+            supply_cap: 1_000_000,
+        }
+        .data(),
+    };
+    let propose_authority = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::UpdateMintConfig {
+            mint_config,
+            mint: mint.pubkey(),
+            authority: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::ProposeAuthority {
+            new_authority: multisig_signer,
+            delay: 0,
+        }
+        .data(),
+    };
+    process(
+        &mut fixture.banks_client,
+        &[initialize_mint, propose_authority],
+        &admin,
+        &[&mint],
+    )
+    .await
+    .unwrap();
+
+    let accept_authority = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::AcceptAuthority {
+            mint_config,
+            mint: mint.pubkey(),
+            pending_authority: multisig_signer,
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::AcceptAuthority {}.data(),
+    };
+    propose_and_execute(&mut fixture, 0, &accept_authority).await;
+
+    // Only the multisig can change the config from now on
+    assert!(process(
+        &mut fixture.banks_client,
+        &[update_mint_config(admin.pubkey())],
+        &admin,
+        &[],
+    )
+    .await
+    .is_err());
+    propose_and_execute(&mut fixture, 1, &update_mint_config(multisig_signer)).await;
+
+    let config: solearn_token::MintConfig = account(&mut fixture.banks_client, mint_config).await;
+    assert_eq!(config.authority, multisig_signer);
+    assert_eq!(config.supply_cap, 2_000_000);
+    assert!(config.paused);
+    assert_eq!(config.paused_by, multisig_signer);
+}
+
+#[tokio::test]
+async fn multisig_signer_updates_learning_program_config() {
+    let mut fixture = setup().await;
+    let admin = fixture.members[0].insecure_clone();
+    let multisig_signer = fixture.multisig_signer;
+
+    let tenant = Keypair::new();
+    let (program_state, _) = Pubkey::find_program_address(
+        &[b"program_state", tenant.pubkey().as_ref()],
+        &solearn_learning::id(),
+    );
+    let update_program_config = |authority: Pubkey| Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::UpdateProgramConfig {
+            program_state,
+            authority,
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::UpdateProgramConfig {
+            pause_program: None,
+            paused_operations: Some(solearn_learning::operation::REWARD_MINTING),
+        }
+        .data(),
+    };
+
+    // A member initializes the program and hands its authority to the multisig
+    let initialize_program = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::InitializeProgram {
+            program_state,
+            tenant: tenant.pubkey(),
+            authority: admin.pubkey(),
+            system_program: system_program::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::InitializeProgram {
+            token_mint: Pubkey::new_unique(),
+            token_program: spl_token::id(),
+        }
+        .data(),
+    };
+    let propose_authority = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::AdminAction {
+            program_state,
+            authority: admin.pubkey(),
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::ProposeAuthority {
+            new_authority: multisig_signer,
+            delay: 0,
+        }
+        .data(),
+    };
+    process(
+        &mut fixture.banks_client,
+        &[initialize_program, propose_authority],
+        &admin,
+        &[&tenant],
+    )
+    .await
+    .unwrap();
+
+    let accept_authority = Instruction {
+        program_id: solearn_learning::id(),
+        accounts: solearn_learning::accounts::AcceptAuthority {
+            program_state,
+            pending_authority: multisig_signer,
+        }
+        .to_account_metas(None),
+        data: solearn_learning::instruction::AcceptAuthority {}.data(),
+    };
+    propose_and_execute(&mut fixture, 0, &accept_authority).await;
+
+    // Only the multisig can change the config from now on
+    assert!(process(
+        &mut fixture.banks_client,
+        &[update_program_config(admin.pubkey())],
+        &admin,
+        &[],
+    )
+    .await
+    .is_err());
+    propose_and_execute(&mut fixture, 1, &update_program_config(multisig_signer)).await;
+
+    let state: solearn_learning::ProgramState = account(&mut fixture.banks_client, program_state).await;
+    assert_eq!(state.authority, multisig_signer);
+    assert_eq!(state.paused_operations, solearn_learning::operation::REWARD_MINTING);
+}
@@ -0,0 +1,696 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solearn_token::MintConfig;
+
+declare_id!("LearnStakingProgram111111111111111111111111");
+
+/// Number of staking tiers, Bronze through Diamond
+pub const TIER_COUNT: usize = 5;
+
+/// Basis point denominator for reward boosts
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Maximum reward boost of a tier: 100%
+pub const MAX_BOOST_BPS: u16 = 10_000;
+
+/// Number of lockup options a stake can choose from
+pub const LOCKUP_OPTION_COUNT: usize = 4;
+
+/// Maximum staking reward weight of a lockup option: 3x
+pub const MAX_WEIGHT_BPS: u16 = 30_000;
+
+/// Shortest lockup option a pool may offer, so a stake cannot be moved in
+/// and out around a single reward
+pub const MIN_LOCKUP_DURATION: i64 = 7 * DAY;
+
+/// Fixed-point scale of `StakingPool::acc_reward_per_share`
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+const DAY: i64 = 24 * 60 * 60;
+
+#[program]
+pub mod solearn_staking {
+    use super::*;
+
+    /// Create the staking pool for a LEARN mint with the tokenomics tiers:
+    /// Bronze 1k (5%), Silver 10k (15%), Gold 50k (30%), Platinum 100k (50%)
+    /// and Diamond 500k LEARN (100% learning reward boost), and lockups of
+    /// 30, 90, 180 and 365 days earning 1x, 1.1x, 1.25x and 1.5x staking
+    /// rewards. Only the authority of the mint can create its pool.
+    pub fn initialize_pool(ctx: Context<InitializePool>) -> Result<()> {
+        let one_token = 10u64
+            .checked_pow(u32::from(ctx.accounts.mint.decimals))
+            .ok_or(ErrorCode::InvalidTiers)?;
+        let tier = |tokens: u64, boost_bps: u16| StakingTier {
+            threshold: tokens.saturating_mul(one_token),
+            boost_bps,
+        };
+
+        let pool = &mut ctx.accounts.staking_pool;
+        pool.authority = ctx.accounts.authority.key();
+        pool.mint = ctx.accounts.mint.key();
+        pool.vault = ctx.accounts.vault.key();
+        pool.reward_vault = ctx.accounts.reward_vault.key();
+        pool.total_staked = 0;
+        pool.total_weight = 0;
+        pool.acc_reward_per_share = 0;
+        pool.undistributed_rewards = 0;
+        pool.lockup_options = [
+            LockupOption { duration: 30 * DAY, weight_bps: 10_000 },
+            LockupOption { duration: 90 * DAY, weight_bps: 11_000 },
+            LockupOption { duration: 180 * DAY, weight_bps: 12_500 },
+            LockupOption { duration: 365 * DAY, weight_bps: 15_000 },
+        ];
+        pool.tiers = [
+            tier(1_000, 500),
+            tier(10_000, 1_500),
+            tier(50_000, 3_000),
+            tier(100_000, 5_000),
+            tier(500_000, 10_000),
+        ];
+        pool.bump = *ctx.bumps.get("staking_pool").unwrap();
+
+        msg!("Staking pool initialized for mint {}", pool.mint);
+
+        Ok(())
+    }
+
+    /// Update the lockup options and tier thresholds and boosts. Existing
+    /// positions keep their reward weight until they next stake.
+    pub fn update_pool_config(
+        ctx: Context<UpdatePoolConfig>,
+        lockup_options: Option<[LockupOption; 4]>,
+        tiers: Option<[StakingTier; 5]>,
+    ) -> Result<()> {
+        let pool = &mut ctx.accounts.staking_pool;
+
+        // Update lockup options if provided
+        if let Some(lockup_options) = lockup_options {
+            validate_lockup_options(&lockup_options)?;
+            pool.lockup_options = lockup_options;
+            msg!("Updated lockup options");
+        }
+
+        // Update tiers if provided
+        if let Some(tiers) = tiers {
+            validate_tiers(&tiers)?;
+            pool.tiers = tiers;
+            msg!("Updated staking tiers");
+        }
+
+        Ok(())
+    }
+
+    /// Stake LEARN into the pool vault under one of the pool's lockup
+    /// options. Every stake restarts the lockup of the whole position; a
+    /// position cannot move to a shorter lockup option.
+    pub fn stake(ctx: Context<Stake>, amount: u64, lockup_option: u8) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let pool = &mut ctx.accounts.staking_pool;
+        let position = &mut ctx.accounts.stake_position;
+
+        if position.owner == Pubkey::default() {
+            position.pool = pool.key();
+            position.owner = ctx.accounts.owner.key();
+            position.bump = *ctx.bumps.get("stake_position").unwrap();
+        }
+        let option = pool
+            .lockup_options
+            .get(usize::from(lockup_option))
+            .copied()
+            .ok_or(ErrorCode::InvalidLockup)?;
+        if position.amount > 0 && lockup_option < position.lockup_option {
+            return Err(ErrorCode::InvalidLockup.into());
+        }
+
+        position.settle_rewards(pool.acc_reward_per_share)?;
+
+        let amount_after = position.amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        let weight_after = weighted_amount(amount_after, option.weight_bps)?;
+        pool.total_staked = pool.total_staked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        pool.total_weight = pool
+            .total_weight
+            .checked_sub(position.weight)
+            .and_then(|total| total.checked_add(weight_after))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        position.amount = amount_after;
+        position.weight = weight_after;
+        position.lockup_option = lockup_option;
+        position.staked_at = now;
+        position.locked_until = now.checked_add(option.duration).ok_or(ErrorCode::MathOverflow)?;
+        position.reset_reward_debt(pool.acc_reward_per_share)?;
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!(
+            "Staked {} tokens for {}, locked until {}",
+            amount,
+            position.owner,
+            position.locked_until
+        );
+
+        Ok(())
+    }
+
+    /// Withdraw staked LEARN once the position's lockup has ended
+    pub fn unstake(ctx: Context<Unstake>, amount: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let pool = &mut ctx.accounts.staking_pool;
+        let position = &mut ctx.accounts.stake_position;
+
+        if now < position.locked_until {
+            return Err(ErrorCode::StakeLocked.into());
+        }
+        if amount == 0 || amount > position.amount {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        position.settle_rewards(pool.acc_reward_per_share)?;
+
+        let option = pool.lockup_options[usize::from(position.lockup_option)];
+        let amount_after = position.amount - amount;
+        // Never let the remaining weight exceed what the position had
+        let weight_after = weighted_amount(amount_after, option.weight_bps)?.min(position.weight);
+        pool.total_staked -= amount;
+        pool.total_weight -= position.weight - weight_after;
+
+        position.amount = amount_after;
+        position.weight = weight_after;
+        position.reset_reward_debt(pool.acc_reward_per_share)?;
+
+        let seeds = &[b"staking-pool".as_ref(), pool.mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        msg!("Unstaked {} tokens for {}", amount, position.owner);
+
+        Ok(())
+    }
+
+    /// Top up the reward pool from the treasury or any other funder. The
+    /// amount is distributed pro rata to current stake weight; if nothing is
+    /// staked it is held until the next top-up.
+    pub fn fund_rewards(ctx: Context<FundRewards>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funder_token_account.to_account_info(),
+            to: ctx.accounts.reward_vault.to_account_info(),
+            authority: ctx.accounts.funder.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        let pool = &mut ctx.accounts.staking_pool;
+        pool.distribute_rewards(amount)?;
+
+        emit!(RewardsFunded {
+            pool: pool.key(),
+            funder: ctx.accounts.funder.key(),
+            amount,
+            acc_reward_per_share: pool.acc_reward_per_share,
+        });
+
+        msg!("Funded {} staking rewards", amount);
+
+        Ok(())
+    }
+
+    /// Transfer all staking rewards accrued by the caller's position
+    pub fn claim_staking_rewards(ctx: Context<ClaimStakingRewards>) -> Result<()> {
+        let pool = &ctx.accounts.staking_pool;
+        let position = &mut ctx.accounts.stake_position;
+
+        position.settle_rewards(pool.acc_reward_per_share)?;
+        let amount = position.unclaimed_rewards;
+        if amount == 0 {
+            return Err(ErrorCode::NothingToClaim.into());
+        }
+        position.unclaimed_rewards = 0;
+
+        let seeds = &[b"staking-pool".as_ref(), pool.mint.as_ref(), &[pool.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.reward_vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: pool.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, amount)?;
+
+        emit!(StakingRewardsClaimed {
+            pool: pool.key(),
+            owner: position.owner,
+            amount,
+        });
+
+        msg!("Claimed {} staking rewards for {}", amount, position.owner);
+
+        Ok(())
+    }
+}
+
+/// `amount` scaled by a lockup weight in basis points, rounded down
+fn weighted_amount(amount: u64, weight_bps: u16) -> Result<u64> {
+    let weighted = u128::from(amount)
+        .checked_mul(u128::from(weight_bps))
+        .ok_or(ErrorCode::MathOverflow)?
+        / u128::from(BPS_DENOMINATOR);
+    u64::try_from(weighted).map_err(|_| ErrorCode::MathOverflow.into())
+}
+
+/// Check that lockup durations are at least MIN_LOCKUP_DURATION and strictly
+/// increasing and that weights are at least 1x, non-decreasing and within
+/// MAX_WEIGHT_BPS
+fn validate_lockup_options(options: &[LockupOption; LOCKUP_OPTION_COUNT]) -> Result<()> {
+    if options[0].duration < MIN_LOCKUP_DURATION || u64::from(options[0].weight_bps) < BPS_DENOMINATOR {
+        return Err(ErrorCode::InvalidLockup.into());
+    }
+    for pair in options.windows(2) {
+        if pair[1].duration <= pair[0].duration || pair[1].weight_bps < pair[0].weight_bps {
+            return Err(ErrorCode::InvalidLockup.into());
+        }
+    }
+    if options[LOCKUP_OPTION_COUNT - 1].weight_bps > MAX_WEIGHT_BPS {
+        return Err(ErrorCode::InvalidLockup.into());
+    }
+
+    Ok(())
+}
+
+/// Check that tier thresholds are positive and strictly increasing and that
+/// boosts are non-decreasing and within MAX_BOOST_BPS
+fn validate_tiers(tiers: &[StakingTier; TIER_COUNT]) -> Result<()> {
+    if tiers[0].threshold == 0 {
+        return Err(ErrorCode::InvalidTiers.into());
+    }
+    for pair in tiers.windows(2) {
+        if pair[1].threshold <= pair[0].threshold || pair[1].boost_bps < pair[0].boost_bps {
+            return Err(ErrorCode::InvalidTiers.into());
+        }
+    }
+    if tiers[TIER_COUNT - 1].boost_bps > MAX_BOOST_BPS {
+        return Err(ErrorCode::InvalidTiers.into());
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializePool<'info> {
+    #[account(
+        init,
+        seeds = [b"staking-pool", mint.key().as_ref()],
+        bump,
+        payer = authority,
+        space = 8 + StakingPool::LEN
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        init,
+        seeds = [b"staking-vault", staking_pool.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = mint,
+        token::authority = staking_pool,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        init,
+        seeds = [b"staking-rewards", staking_pool.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = mint,
+        token::authority = staking_pool,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// The solearn_token MintConfig of `mint`, passed when the mint is
+    /// managed by solearn_token
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        seeds::program = solearn_token::ID,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// The SPL mint authority, or the MintConfig authority of a mint
+    /// managed by solearn_token
+    #[account(
+        mut,
+        constraint = solearn_token::controls_mint(&mint, mint_config.as_deref(), &authority.key())
+            @ ErrorCode::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", staking_pool.mint.as_ref()],
+        bump = staking_pool.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Stake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", staking_pool.mint.as_ref()],
+        bump = staking_pool.bump,
+        has_one = vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        init_if_needed,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + StakePosition::LEN
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == staking_pool.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct Unstake<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", staking_pool.mint.as_ref()],
+        bump = staking_pool.bump,
+        has_one = vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == staking_pool.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct FundRewards<'info> {
+    #[account(
+        mut,
+        seeds = [b"staking-pool", staking_pool.mint.as_ref()],
+        bump = staking_pool.bump,
+        has_one = reward_vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = funder_token_account.mint == staking_pool.mint,
+        constraint = funder_token_account.owner == funder.key(),
+    )]
+    pub funder_token_account: Account<'info, TokenAccount>,
+
+    pub funder: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimStakingRewards<'info> {
+    #[account(
+        seeds = [b"staking-pool", staking_pool.mint.as_ref()],
+        bump = staking_pool.bump,
+        has_one = reward_vault,
+    )]
+    pub staking_pool: Account<'info, StakingPool>,
+
+    #[account(
+        mut,
+        seeds = [b"stake", staking_pool.key().as_ref(), owner.key().as_ref()],
+        bump = stake_position.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub stake_position: Account<'info, StakePosition>,
+
+    #[account(mut)]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == staking_pool.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Lockup duration a stake commits to and its staking reward weight
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LockupOption {
+    pub duration: i64,
+    pub weight_bps: u16,
+}
+
+impl LockupOption {
+    pub const LEN: usize = 8 + 2;
+}
+
+/// Minimum stake for a tier and the learning reward boost it grants
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StakingTier {
+    pub threshold: u64,
+    pub boost_bps: u16,
+}
+
+impl StakingTier {
+    pub const LEN: usize = 8 + 2;
+}
+
+#[account]
+#[derive(Default)]
+pub struct StakingPool {
+    pub authority: Pubkey,             // 32 bytes
+    pub mint: Pubkey,                  // 32 bytes
+    pub vault: Pubkey,                 // 32 bytes
+    pub reward_vault: Pubkey,          // 32 bytes
+    pub total_staked: u64,             // 8 bytes
+    pub total_weight: u64,             // 8 bytes, sum of position weights
+    pub acc_reward_per_share: u128,    // 16 bytes, rewards per weight unit * REWARD_PRECISION
+    pub undistributed_rewards: u64,    // 8 bytes, funded while nothing was staked
+    pub lockup_options: [LockupOption; 4], // LOCKUP_OPTION_COUNT * LockupOption::LEN bytes
+    pub tiers: [StakingTier; 5],       // TIER_COUNT * StakingTier::LEN bytes, Bronze to Diamond
+    pub bump: u8,                      // 1 byte
+}
+
+impl StakingPool {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 16 + 8
+        + LOCKUP_OPTION_COUNT * LockupOption::LEN
+        + TIER_COUNT * StakingTier::LEN + 1;
+
+    /// Add `amount` to the reward-per-share index. The division rounds down,
+    /// so the dust stays in the reward vault rather than being overpaid.
+    pub fn distribute_rewards(&mut self, amount: u64) -> Result<()> {
+        let amount = amount
+            .checked_add(self.undistributed_rewards)
+            .ok_or(ErrorCode::MathOverflow)?;
+        if self.total_weight == 0 {
+            self.undistributed_rewards = amount;
+            return Ok(());
+        }
+
+        let increment = u128::from(amount)
+            .checked_mul(REWARD_PRECISION)
+            .ok_or(ErrorCode::MathOverflow)?
+            / u128::from(self.total_weight);
+        self.acc_reward_per_share = self
+            .acc_reward_per_share
+            .checked_add(increment)
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.undistributed_rewards = 0;
+
+        Ok(())
+    }
+
+    /// Learning reward boost in basis points for a staked amount: the boost
+    /// of the highest tier reached, or zero below Bronze
+    pub fn boost_bps(&self, staked: u64) -> u16 {
+        self.tiers
+            .iter()
+            .rev()
+            .find(|tier| staked >= tier.threshold)
+            .map_or(0, |tier| tier.boost_bps)
+    }
+
+    /// Scale `amount` by the boost for `staked`, rounding down
+    pub fn boosted_amount(&self, amount: u64, staked: u64) -> u64 {
+        let boosted = u128::from(amount) * u128::from(BPS_DENOMINATOR + u64::from(self.boost_bps(staked)))
+            / u128::from(BPS_DENOMINATOR);
+        u64::try_from(boosted).unwrap_or(u64::MAX)
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct StakePosition {
+    pub pool: Pubkey,                  // 32 bytes
+    pub owner: Pubkey,                 // 32 bytes
+    pub amount: u64,                   // 8 bytes
+    pub staked_at: i64,                // 8 bytes
+    pub locked_until: i64,             // 8 bytes
+    pub lockup_option: u8,             // 1 byte
+    pub weight: u64,                   // 8 bytes, amount scaled by the lockup weight
+    pub reward_debt: u128,             // 16 bytes, weight * acc_reward_per_share at the last settlement
+    pub unclaimed_rewards: u64,        // 8 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl StakePosition {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1 + 8 + 16 + 8 + 1;
+
+    /// Move rewards accrued since the last settlement into
+    /// `unclaimed_rewards`, rounding down
+    pub fn settle_rewards(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        let accrued = u128::from(self.weight)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let pending = accrued
+            .checked_sub(self.reward_debt)
+            .ok_or(ErrorCode::MathOverflow)?
+            / REWARD_PRECISION;
+        self.unclaimed_rewards = u64::try_from(pending)
+            .ok()
+            .and_then(|pending| self.unclaimed_rewards.checked_add(pending))
+            .ok_or(ErrorCode::MathOverflow)?;
+        self.reward_debt = accrued;
+
+        Ok(())
+    }
+
+    /// Reset the reward debt after the position's weight changed
+    pub fn reset_reward_debt(&mut self, acc_reward_per_share: u128) -> Result<()> {
+        self.reward_debt = u128::from(self.weight)
+            .checked_mul(acc_reward_per_share)
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        Ok(())
+    }
+}
+
+#[event]
+pub struct RewardsFunded {
+    pub pool: Pubkey,
+    pub funder: Pubkey,
+    pub amount: u64,
+    pub acc_reward_per_share: u128,
+}
+
+#[event]
+pub struct StakingRewardsClaimed {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+
+    #[msg("Amount must be greater than zero and within the stake")]
+    InvalidAmount,
+
+    #[msg("Invalid lockup duration")]
+    InvalidLockup,
+
+    #[msg("Tier thresholds must increase and boosts must not decrease or exceed 100%")]
+    InvalidTiers,
+
+    #[msg("Stake is still locked")]
+    StakeLocked,
+
+    #[msg("No staking rewards to claim")]
+    NothingToClaim,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+}
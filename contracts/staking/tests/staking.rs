@@ -0,0 +1,492 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_staking::{LockupOption, StakePosition, StakingPool, MIN_LOCKUP_DURATION};
+
+const DAY: i64 = 24 * 60 * 60;
+const START: i64 = 1_700_000_000;
+
+/// Lockup option indices of the default pool: 30 days at 1x and 365 days at
+/// 1.5x staking reward weight
+const SHORTEST_LOCKUP: u8 = 0;
+const LONGEST_LOCKUP: u8 = 3;
+
+/// A wallet holding LEARN that it can stake
+struct Staker {
+    wallet: Keypair,
+    token_account: Pubkey,
+}
+
+/// A staking pool over a fresh mint. The mint authority created the pool and
+/// funds its rewards from `funder_account`.
+struct Fixture {
+    context: ProgramTestContext,
+    authority: Keypair,
+    mint: Pubkey,
+    funder_account: Pubkey,
+    now: i64,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+fn staking_pool(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"staking-pool", mint.as_ref()], &solearn_staking::id()).0
+}
+
+/// A token account of the pool seeded by `seed`: its vault or reward vault
+fn pool_account(pool: &Pubkey, seed: &[u8]) -> Pubkey {
+    Pubkey::find_program_address(&[seed, pool.as_ref()], &solearn_staking::id()).0
+}
+
+fn initialize_pool(mint: &Pubkey, authority: &Pubkey) -> Instruction {
+    let pool = staking_pool(mint);
+    Instruction {
+        program_id: solearn_staking::id(),
+        accounts: solearn_staking::accounts::InitializePool {
+            staking_pool: pool,
+            vault: pool_account(&pool, b"staking-vault"),
+            reward_vault: pool_account(&pool, b"staking-rewards"),
+            mint: *mint,
+            mint_config: None,
+            authority: *authority,
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_staking::instruction::InitializePool {}.data(),
+    }
+}
+
+impl Fixture {
+    /// Send a transaction at the fixture's current time
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = self.now;
+        self.context.set_sysvar(&clock);
+
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            all_signers.as_slice(),
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    fn staking_pool(&self) -> Pubkey {
+        staking_pool(&self.mint)
+    }
+
+    fn vault(&self) -> Pubkey {
+        pool_account(&self.staking_pool(), b"staking-vault")
+    }
+
+    fn reward_vault(&self) -> Pubkey {
+        pool_account(&self.staking_pool(), b"staking-rewards")
+    }
+
+    fn stake_position(&self, owner: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"stake", self.staking_pool().as_ref(), owner.as_ref()],
+            &solearn_staking::id(),
+        )
+        .0
+    }
+
+    async fn pool(&mut self) -> StakingPool {
+        let address = self.staking_pool();
+        self.account(address).await
+    }
+
+    async fn position(&mut self, staker: &Staker) -> StakePosition {
+        let address = self.stake_position(&staker.wallet.pubkey());
+        self.account(address).await
+    }
+
+    /// Create a token account of the pool's mint for `owner` holding `amount`
+    async fn token_account(&mut self, owner: &Pubkey, amount: u64) -> Pubkey {
+        let authority = self.authority.insecure_clone();
+        let token_account = Keypair::new();
+        let rent = self.context.banks_client.get_rent().await.unwrap();
+        let instructions = [
+            system_instruction::create_account(
+                &authority.pubkey(),
+                &token_account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token_account.pubkey(),
+                &self.mint,
+                owner,
+            )
+            .unwrap(),
+            spl_token::instruction::mint_to(
+                &spl_token::id(),
+                &self.mint,
+                &token_account.pubkey(),
+                &authority.pubkey(),
+                &[],
+                amount,
+            )
+            .unwrap(),
+        ];
+        self.process(&instructions, &authority, &[&token_account])
+            .await
+            .unwrap();
+        token_account.pubkey()
+    }
+
+    /// Fund a new wallet and give it `amount` LEARN
+    async fn new_staker(&mut self, amount: u64) -> Staker {
+        let wallet = Keypair::new();
+        let authority = self.authority.insecure_clone();
+        let fund = system_instruction::transfer(&authority.pubkey(), &wallet.pubkey(), LAMPORTS_PER_SOL);
+        self.process(&[fund], &authority, &[]).await.unwrap();
+        let token_account = self.token_account(&wallet.pubkey(), amount).await;
+
+        Staker { wallet, token_account }
+    }
+
+    async fn stake(&mut self, staker: &Staker, amount: u64, lockup_option: u8) -> Result<(), BanksClientError> {
+        let instruction = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::Stake {
+                staking_pool: self.staking_pool(),
+                stake_position: self.stake_position(&staker.wallet.pubkey()),
+                vault: self.vault(),
+                owner_token_account: staker.token_account,
+                owner: staker.wallet.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::Stake { amount, lockup_option }.data(),
+        };
+        self.process(&[instruction], &staker.wallet, &[]).await
+    }
+
+    async fn unstake(&mut self, staker: &Staker, amount: u64) -> Result<(), BanksClientError> {
+        let instruction = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::Unstake {
+                staking_pool: self.staking_pool(),
+                stake_position: self.stake_position(&staker.wallet.pubkey()),
+                vault: self.vault(),
+                owner_token_account: staker.token_account,
+                owner: staker.wallet.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::Unstake { amount }.data(),
+        };
+        self.process(&[instruction], &staker.wallet, &[]).await
+    }
+
+    async fn fund_rewards(&mut self, amount: u64) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::FundRewards {
+                staking_pool: self.staking_pool(),
+                reward_vault: self.reward_vault(),
+                funder_token_account: self.funder_account,
+                funder: authority.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::FundRewards { amount }.data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+
+    async fn claim(&mut self, staker: &Staker) -> Result<(), BanksClientError> {
+        let instruction = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::ClaimStakingRewards {
+                staking_pool: self.staking_pool(),
+                stake_position: self.stake_position(&staker.wallet.pubkey()),
+                reward_vault: self.reward_vault(),
+                owner_token_account: staker.token_account,
+                owner: staker.wallet.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::ClaimStakingRewards {}.data(),
+        };
+        self.process(&[instruction], &staker.wallet, &[]).await
+    }
+
+    async fn update_lockup_options(&mut self, lockup_options: [LockupOption; 4]) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_staking::id(),
+            accounts: solearn_staking::accounts::UpdatePoolConfig {
+                staking_pool: self.staking_pool(),
+                authority: authority.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_staking::instruction::UpdatePoolConfig {
+                lockup_options: Some(lockup_options),
+                tiers: None,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+}
+
+/// Start a validator with a LEARN mint, its staking pool and a funder
+/// account holding rewards
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_staking",
+        solearn_staking::id(),
+        processor!(solearn_staking::entry),
+    );
+
+    let authority = Keypair::new();
+    program_test.add_account(authority.pubkey(), funded_account());
+
+    let mut context = program_test.start_with_context().await;
+    let mut clock: Clock = context.banks_client.get_sysvar().await.unwrap();
+    clock.unix_timestamp = START;
+    context.set_sysvar(&clock);
+
+    let mint = Keypair::new();
+    let mut fixture = Fixture {
+        context,
+        authority: authority.insecure_clone(),
+        mint: mint.pubkey(),
+        funder_account: Pubkey::default(),
+        now: START,
+    };
+
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &authority.pubkey(), None, 6)
+            .unwrap(),
+        initialize_pool(&mint.pubkey(), &authority.pubkey()),
+    ];
+    fixture.process(&create_mint, &authority, &[&mint]).await.unwrap();
+    fixture.funder_account = fixture.token_account(&authority.pubkey(), 1_000_000).await;
+
+    fixture
+}
+
+#[tokio::test]
+async fn only_the_mint_authority_creates_the_pool() {
+    let mut fixture = setup().await;
+    let authority = fixture.authority.insecure_clone();
+    let outsider = fixture.new_staker(0).await.wallet;
+
+    // A second mint whose pool the outsider tries to claim first
+    let mint = Keypair::new();
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    let create_mint = [
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &authority.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    fixture.process(&create_mint, &authority, &[&mint]).await.unwrap();
+
+    let squat = initialize_pool(&mint.pubkey(), &outsider.pubkey());
+    assert!(fixture.process(&[squat], &outsider, &[]).await.is_err());
+
+    let initialize = initialize_pool(&mint.pubkey(), &authority.pubkey());
+    fixture.process(&[initialize], &authority, &[]).await.unwrap();
+}
+
+#[tokio::test]
+async fn rewards_accrue_pro_rata_to_stake_weight() {
+    let mut fixture = setup().await;
+    let short = fixture.new_staker(1_000).await;
+    let long = fixture.new_staker(1_000).await;
+
+    // Rewards funded while nothing is staked wait for the next top-up
+    fixture.fund_rewards(500).await.unwrap();
+    assert_eq!(fixture.pool().await.undistributed_rewards, 500);
+
+    fixture.stake(&short, 1_000, SHORTEST_LOCKUP).await.unwrap();
+    fixture.stake(&long, 1_000, LONGEST_LOCKUP).await.unwrap();
+    let pool = fixture.pool().await;
+    assert_eq!(pool.total_staked, 2_000);
+    assert_eq!(pool.total_weight, 2_500);
+
+    // 2,500 split over weights of 1,000 and 1,500
+    fixture.fund_rewards(2_000).await.unwrap();
+    assert_eq!(fixture.pool().await.undistributed_rewards, 0);
+
+    fixture.claim(&short).await.unwrap();
+    fixture.claim(&long).await.unwrap();
+    assert_eq!(fixture.balance(short.token_account).await, 1_000);
+    assert_eq!(fixture.balance(long.token_account).await, 1_500);
+    assert_eq!(fixture.position(&long).await.unclaimed_rewards, 0);
+
+    // Nothing accrued since the last claim
+    assert!(fixture.claim(&short).await.is_err());
+
+    // Rewards accrue only from when a stake joins
+    let late = fixture.new_staker(1_000).await;
+    fixture.stake(&late, 1_000, SHORTEST_LOCKUP).await.unwrap();
+    assert!(fixture.claim(&late).await.is_err());
+    fixture.fund_rewards(3_500).await.unwrap();
+    fixture.claim(&late).await.unwrap();
+    assert_eq!(fixture.balance(late.token_account).await, 1_000);
+}
+
+#[tokio::test]
+async fn partial_unstake_keeps_accrued_rewards() {
+    let mut fixture = setup().await;
+    let staker = fixture.new_staker(1_000).await;
+    fixture.stake(&staker, 1_000, SHORTEST_LOCKUP).await.unwrap();
+    fixture.fund_rewards(1_000).await.unwrap();
+
+    // The stake stays locked for the 30 day lockup
+    fixture.now += 30 * DAY - 1;
+    assert!(fixture.unstake(&staker, 400).await.is_err());
+    fixture.now += 1;
+
+    // Neither nothing nor more than the stake can be withdrawn
+    assert!(fixture.unstake(&staker, 0).await.is_err());
+    assert!(fixture.unstake(&staker, 1_001).await.is_err());
+
+    fixture.unstake(&staker, 400).await.unwrap();
+    assert_eq!(fixture.balance(staker.token_account).await, 400);
+    let position = fixture.position(&staker).await;
+    assert_eq!(position.amount, 600);
+    assert_eq!(position.weight, 600);
+    assert_eq!(position.unclaimed_rewards, 1_000);
+    let pool = fixture.pool().await;
+    assert_eq!(pool.total_staked, 600);
+    assert_eq!(pool.total_weight, 600);
+
+    // The remaining stake earns alongside a new one of equal weight
+    let other = fixture.new_staker(600).await;
+    fixture.stake(&other, 600, SHORTEST_LOCKUP).await.unwrap();
+    fixture.fund_rewards(1_200).await.unwrap();
+
+    fixture.claim(&staker).await.unwrap();
+    assert_eq!(fixture.balance(staker.token_account).await, 400 + 1_600);
+    fixture.claim(&other).await.unwrap();
+    assert_eq!(fixture.balance(other.token_account).await, 600);
+
+    // Withdrawing the rest empties the position
+    fixture.unstake(&staker, 600).await.unwrap();
+    assert_eq!(fixture.balance(staker.token_account).await, 1_000 + 1_600);
+    assert_eq!(fixture.position(&staker).await.weight, 0);
+    assert_eq!(fixture.balance(fixture.vault()).await, 600);
+}
+
+#[tokio::test]
+async fn reward_rounding_favours_the_pool() {
+    let mut fixture = setup().await;
+    let mut stakers = Vec::new();
+    for _ in 0..3 {
+        let staker = fixture.new_staker(1).await;
+        fixture.stake(&staker, 1, SHORTEST_LOCKUP).await.unwrap();
+        stakers.push(staker);
+    }
+
+    // 10 over a weight of 3 pays each stake 3 and leaves the remainder in
+    // the reward vault
+    fixture.fund_rewards(10).await.unwrap();
+    for staker in &stakers {
+        fixture.claim(staker).await.unwrap();
+        assert_eq!(fixture.balance(staker.token_account).await, 3);
+    }
+    assert_eq!(fixture.balance(fixture.reward_vault()).await, 1);
+
+    // Lockup weights round down too: 1 token at 1.1x weighs 1
+    let weighted = fixture.new_staker(1).await;
+    fixture.stake(&weighted, 1, SHORTEST_LOCKUP + 1).await.unwrap();
+    assert_eq!(fixture.position(&weighted).await.weight, 1);
+
+    // 6 over a weight of 4 pays each stake 1 and the remainders stay
+    // behind with the earlier dust
+    fixture.fund_rewards(6).await.unwrap();
+    for staker in &stakers {
+        fixture.claim(staker).await.unwrap();
+        assert_eq!(fixture.balance(staker.token_account).await, 4);
+    }
+    fixture.claim(&weighted).await.unwrap();
+    assert_eq!(fixture.balance(weighted.token_account).await, 1);
+    assert_eq!(fixture.balance(fixture.reward_vault()).await, 3);
+}
+
+#[tokio::test]
+async fn lockup_options_require_the_minimum_lockup() {
+    let mut fixture = setup().await;
+    let mut lockup_options = fixture.pool().await.lockup_options;
+
+    lockup_options[0].duration = 0;
+    assert!(fixture.update_lockup_options(lockup_options).await.is_err());
+    lockup_options[0].duration = MIN_LOCKUP_DURATION - 1;
+    assert!(fixture.update_lockup_options(lockup_options).await.is_err());
+
+    lockup_options[0].duration = MIN_LOCKUP_DURATION;
+    fixture.update_lockup_options(lockup_options).await.unwrap();
+    assert_eq!(fixture.pool().await.lockup_options[0].duration, MIN_LOCKUP_DURATION);
+}
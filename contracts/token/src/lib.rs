@@ -1,9 +1,10 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Mint, Token, TokenAccount};
+use anchor_spl::token::spl_token::instruction::AuthorityType;
 use solana_program::program_option::COption;
 use std::convert::TryFrom;
 
-declare_id!("LearnTokenProgramID111111111111111111111111111");
+declare_id!("LearnTokenProgram11111111111111111111111111");
 
 #[program]
 pub mod solearn_token {
@@ -18,7 +19,6 @@ pub mod solearn_token {
         // Set default values for the mint
         let mint_info = &ctx.accounts.mint;
         let authority_info = &ctx.accounts.authority;
-        let rent = Rent::get()?;
 
         // Validate supply cap
         if supply_cap == 0 {
@@ -34,15 +34,231 @@ pub mod solearn_token {
         mint_config.mint = mint_info.key();
         mint_config.decimals = decimals;
         mint_config.paused = false;
+        mint_config.paused_by = Pubkey::default();
+        mint_config.paused_at = 0;
+        mint_config.pause_reason = PauseReason::default();
+        mint_config.guardians = Vec::new();
         mint_config.last_mint_timestamp = 0;
-        mint_config.mint_cooldown = 0; // No cooldown by default
+        mint_config.rate_limit_amount = 0; // No rate limit by default
+        mint_config.rate_limit_window = 0;
+        mint_config.rate_limit_available = 0;
+        mint_config.rate_limit_updated_at = 0;
+        mint_config.emission_minter = Pubkey::default();
+        mint_config.total_allocated = 0;
+        mint_config.total_burned = 0;
+        mint_config.burned_by_reason = [0; BurnReason::COUNT];
+        mint_config.compliance_authority = Pubkey::default();
+        mint_config.pending_authority = Pubkey::default();
+        mint_config.authority_transfer_eta = 0;
 
         msg!("LEARN token mint initialized with supply cap: {}", supply_cap);
         
         Ok(())
     }
 
-    /// Mint new LEARN tokens
+    /// Create a named allocation bucket, such as "team" or "learning-mining",
+    /// with its own cap. Bucket caps together may not exceed the supply cap.
+    pub fn create_allocation_bucket(
+        ctx: Context<CreateAllocationBucket>,
+        name: String,
+        cap: u64,
+    ) -> Result<()> {
+        if name.is_empty() || name.len() > AllocationBucket::MAX_NAME_LEN {
+            return Err(ErrorCode::InvalidBucketName.into());
+        }
+
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.total_allocated = mint_config
+            .total_allocated
+            .checked_add(cap)
+            .filter(|allocated| *allocated <= mint_config.supply_cap)
+            .ok_or(ErrorCode::AllocationExceedsSupplyCap)?;
+
+        let bucket = &mut ctx.accounts.allocation_bucket;
+        bucket.mint = ctx.accounts.mint.key();
+        bucket.name = name;
+        bucket.cap = cap;
+        bucket.minted = 0;
+        bucket.bump = *ctx.bumps.get("allocation_bucket").unwrap();
+
+        msg!("Allocation bucket {} created with cap: {}", bucket.name, cap);
+
+        Ok(())
+    }
+
+    /// Change the cap of an allocation bucket
+    pub fn update_allocation_bucket(ctx: Context<UpdateAllocationBucket>, cap: u64) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+        let bucket = &mut ctx.accounts.allocation_bucket;
+
+        // Cannot lower a cap below what the bucket already minted
+        if cap < bucket.minted {
+            return Err(ErrorCode::InvalidBucketCap.into());
+        }
+
+        mint_config.total_allocated = (mint_config.total_allocated - bucket.cap)
+            .checked_add(cap)
+            .filter(|allocated| *allocated <= mint_config.supply_cap)
+            .ok_or(ErrorCode::AllocationExceedsSupplyCap)?;
+        bucket.cap = cap;
+
+        msg!("Updated cap of allocation bucket {} to: {}", bucket.name, cap);
+
+        Ok(())
+    }
+
+    /// Return the cap and minted amount of every allocation bucket passed in
+    /// the remaining accounts, so minting can be reconciled against the
+    /// published token distribution
+    pub fn get_allocation_summary(ctx: Context<GetAllocationSummary>) -> Result<AllocationSummary> {
+        let mint_config = &ctx.accounts.mint_config;
+
+        let mut buckets = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts.iter() {
+            if info.owner != &crate::ID {
+                return Err(ErrorCode::InvalidAllocationBucket.into());
+            }
+            let bucket = AllocationBucket::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            if bucket.mint != mint_config.mint {
+                return Err(ErrorCode::InvalidAllocationBucket.into());
+            }
+            buckets.push(AllocationBucketSummary {
+                name: bucket.name,
+                cap: bucket.cap,
+                minted: bucket.minted,
+            });
+        }
+
+        Ok(AllocationSummary {
+            supply_cap: mint_config.supply_cap,
+            total_allocated: mint_config.total_allocated,
+            total_minted: mint_config.total_minted,
+            buckets,
+        })
+    }
+
+    /// Grant a minter, such as the learning program's PDA or the ecosystem
+    /// fund, its own minting budget drawn from one allocation bucket. A
+    /// non-zero `window_duration` also caps how much it may mint within
+    /// each window of that many seconds.
+    pub fn grant_minter_allowance(
+        ctx: Context<GrantMinterAllowance>,
+        minter: Pubkey,
+        quota: u64,
+        window_duration: u64,
+        window_limit: u64,
+    ) -> Result<()> {
+        let allowance = &mut ctx.accounts.minter_allowance;
+        allowance.mint = ctx.accounts.mint.key();
+        allowance.minter = minter;
+        allowance.allocation_bucket = ctx.accounts.allocation_bucket.key();
+        allowance.quota = quota;
+        allowance.minted = 0;
+        allowance.window_duration = window_duration;
+        allowance.window_limit = window_limit;
+        allowance.window_start = 0;
+        allowance.window_minted = 0;
+        allowance.bump = *ctx.bumps.get("minter_allowance").unwrap();
+
+        msg!("Granted minter {} a quota of {} tokens", minter, quota);
+
+        Ok(())
+    }
+
+    /// Adjust an existing minter allowance
+    pub fn update_minter_allowance(
+        ctx: Context<UpdateMinterAllowance>,
+        quota: Option<u64>,
+        window_duration: Option<u64>,
+        window_limit: Option<u64>,
+    ) -> Result<()> {
+        let allowance = &mut ctx.accounts.minter_allowance;
+
+        // Update quota if provided, never below what was already minted
+        if let Some(quota) = quota {
+            if quota < allowance.minted {
+                return Err(ErrorCode::InvalidAllowanceQuota.into());
+            }
+            allowance.quota = quota;
+            msg!("Updated quota of minter {} to: {}", allowance.minter, quota);
+        }
+
+        // Update rate window if provided; a new window starts on the next mint
+        if let Some(duration) = window_duration {
+            allowance.window_duration = duration;
+            allowance.window_start = 0;
+            allowance.window_minted = 0;
+            msg!("Updated window of minter {} to: {} seconds", allowance.minter, duration);
+        }
+
+        if let Some(limit) = window_limit {
+            allowance.window_limit = limit;
+            msg!("Updated window limit of minter {} to: {}", allowance.minter, limit);
+        }
+
+        Ok(())
+    }
+
+    /// Create the learning-mining emission schedule for a mint. Once set,
+    /// every mint by `minter` draws from the allowance of the current year
+    /// since `genesis_timestamp`; the last entry applies to all later years.
+    pub fn initialize_emission_schedule(
+        ctx: Context<InitializeEmissionSchedule>,
+        minter: Pubkey,
+        genesis_timestamp: u64,
+        yearly_emissions: [u64; EmissionSchedule::SCHEDULED_YEARS],
+        unused_emission_policy: UnusedEmissionPolicy,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.emission_schedule;
+        schedule.mint = ctx.accounts.mint.key();
+        schedule.minter = minter;
+        schedule.genesis_timestamp = genesis_timestamp;
+        schedule.yearly_emissions = yearly_emissions;
+        schedule.unused_emission_policy = unused_emission_policy;
+        schedule.current_year = 0;
+        schedule.current_year_minted = 0;
+        schedule.carried_over = 0;
+        schedule.bump = *ctx.bumps.get("emission_schedule").unwrap();
+
+        ctx.accounts.mint_config.emission_minter = minter;
+
+        msg!(
+            "Emission schedule initialized for minter {} starting at {}",
+            minter,
+            genesis_timestamp
+        );
+
+        Ok(())
+    }
+
+    /// Change whether unused yearly emission rolls over or expires. Takes
+    /// effect from the next year boundary onwards; year boundaries already
+    /// passed are settled under the previous policy first.
+    pub fn update_emission_policy(
+        ctx: Context<UpdateEmissionSchedule>,
+        unused_emission_policy: UnusedEmissionPolicy,
+    ) -> Result<()> {
+        let schedule = &mut ctx.accounts.emission_schedule;
+        let now = Clock::get()?.unix_timestamp as u64;
+        if let Some(year) = schedule.year_at(now) {
+            schedule.advance_to_year(year);
+        }
+        schedule.unused_emission_policy = unused_emission_policy;
+
+        msg!("Updated unused emission policy to: {:?}", unused_emission_policy);
+
+        Ok(())
+    }
+
+    /// Revoke a minter allowance and close its account
+    pub fn revoke_minter_allowance(ctx: Context<RevokeMinterAllowance>) -> Result<()> {
+        msg!("Revoked minter: {}", ctx.accounts.minter_allowance.minter);
+
+        Ok(())
+    }
+
+    /// Mint new LEARN tokens. The signer must hold a minter allowance, which
+    /// programs such as solearn_learning can use by signing through CPI.
     pub fn mint_tokens(
         ctx: Context<MintTokens>,
         amount: u64,
@@ -56,19 +272,33 @@ pub mod solearn_token {
             return Err(ErrorCode::MintingPaused.into());
         }
 
-        // Check cooldown period
-        if mint_config.mint_cooldown > 0 && now < mint_config.last_mint_timestamp + mint_config.mint_cooldown {
-            return Err(ErrorCode::MintCooldownNotMet.into());
-        }
-
         // Check supply cap
         if mint_config.total_minted + amount > mint_config.supply_cap {
             return Err(ErrorCode::SupplyCapExceeded.into());
         }
 
+        // Check and consume the global rate limit
+        mint_config.consume_rate_limit(amount, now)?;
+
+        // Check and record the minter's own budget and its allocation bucket
+        ctx.accounts.minter_allowance.record_mint(amount, now)?;
+        ctx.accounts.allocation_bucket.record_mint(amount)?;
+
+        // Learning-mining emissions must stay within this year's schedule
+        if mint_config.emission_minter == ctx.accounts.authority.key() {
+            let schedule = ctx
+                .accounts
+                .emission_schedule
+                .as_mut()
+                .ok_or(ErrorCode::EmissionScheduleRequired)?;
+            schedule.record_emission(amount, now)?;
+        }
+
         // Mint tokens to the specified recipient
+        let mint_key = ctx.accounts.mint.key();
         let seeds = &[
             b"mint-authority".as_ref(),
+            mint_key.as_ref(),
             &[*ctx.bumps.get("mint_authority").unwrap()],
         ];
         let signer = &[&seeds[..]];
@@ -96,46 +326,300 @@ pub mod solearn_token {
     /// Update mint configuration parameters
     pub fn update_mint_config(
         ctx: Context<UpdateMintConfig>,
-        new_authority: Option<Pubkey>,
         new_supply_cap: Option<u64>,
-        new_cooldown: Option<u64>,
+        new_rate_limit: Option<RateLimit>,
         pause_minting: Option<bool>,
+        new_compliance_authority: Option<Pubkey>,
     ) -> Result<()> {
         let mint_config = &mut ctx.accounts.mint_config;
-
-        // Update mint authority if provided
-        if let Some(authority) = new_authority {
-            mint_config.authority = authority;
-            msg!("Updated mint authority to: {}", authority);
-        }
+        let now = Clock::get()?.unix_timestamp as u64;
 
         // Update supply cap if provided
         if let Some(supply_cap) = new_supply_cap {
-            // Cannot lower supply cap below already minted amount
-            if supply_cap < mint_config.total_minted {
+            // Cannot lower supply cap below already minted or allocated amounts
+            if supply_cap < mint_config.total_minted || supply_cap < mint_config.total_allocated {
                 return Err(ErrorCode::InvalidSupplyCap.into());
             }
             mint_config.supply_cap = supply_cap;
             msg!("Updated supply cap to: {}", supply_cap);
         }
 
-        // Update cooldown if provided
-        if let Some(cooldown) = new_cooldown {
-            mint_config.mint_cooldown = cooldown;
-            msg!("Updated mint cooldown to: {} seconds", cooldown);
+        // Update rate limit if provided; the token bucket starts full
+        if let Some(rate_limit) = new_rate_limit {
+            if rate_limit.amount > 0 && rate_limit.window == 0 {
+                return Err(ErrorCode::InvalidRateLimit.into());
+            }
+            mint_config.rate_limit_amount = rate_limit.amount;
+            mint_config.rate_limit_window = rate_limit.window;
+            mint_config.rate_limit_available = rate_limit.amount;
+            mint_config.rate_limit_updated_at = now;
+            msg!(
+                "Updated mint rate limit to: {} tokens per {} seconds",
+                rate_limit.amount,
+                rate_limit.window
+            );
         }
 
         // Update pause state if provided
         if let Some(pause) = pause_minting {
-            mint_config.paused = pause;
+            let authority = ctx.accounts.authority.key();
+            if pause {
+                mint_config.record_pause(authority, PauseReason::Administrative, now);
+                emit!(MintingPaused {
+                    config: mint_config.key(),
+                    paused_by: authority,
+                    reason: PauseReason::Administrative,
+                    paused_at: now,
+                });
+            } else {
+                mint_config.record_unpause();
+                emit!(MintingUnpaused {
+                    config: mint_config.key(),
+                    authority,
+                });
+            }
             msg!("Minting is now {}", if pause { "paused" } else { "active" });
         }
 
+        // Update compliance authority if provided
+        if let Some(compliance_authority) = new_compliance_authority {
+            mint_config.compliance_authority = compliance_authority;
+            msg!("Updated compliance authority to: {}", compliance_authority);
+        }
+
+        Ok(())
+    }
+
+    /// Replace the guardian set allowed to pause minting in an emergency
+    pub fn set_guardians(ctx: Context<UpdateMintConfig>, guardians: Vec<Pubkey>) -> Result<()> {
+        if guardians.len() > MintConfig::MAX_GUARDIANS {
+            return Err(ErrorCode::TooManyGuardians.into());
+        }
+
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.guardians = guardians;
+
+        msg!("Updated guardian set to {} keys", mint_config.guardians.len());
+
+        Ok(())
+    }
+
+    /// Pause minting immediately. Callable by any guardian or the authority;
+    /// only the authority can unpause through `update_mint_config`.
+    pub fn emergency_pause(ctx: Context<EmergencyPause>, reason: PauseReason) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+        let guardian = ctx.accounts.guardian.key();
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        if mint_config.paused {
+            return Err(ErrorCode::MintingPaused.into());
+        }
+
+        mint_config.record_pause(guardian, reason, now);
+
+        emit!(MintingPaused {
+            config: mint_config.key(),
+            paused_by: guardian,
+            reason,
+            paused_at: now,
+        });
+
+        msg!("Minting paused by {} ({:?})", guardian, reason);
+
+        Ok(())
+    }
+
+    /// Return the current pause state for off-chain monitors
+    pub fn get_pause_state(ctx: Context<GetPauseState>) -> Result<PauseState> {
+        let mint_config = &ctx.accounts.mint_config;
+
+        Ok(PauseState {
+            paused: mint_config.paused,
+            paused_by: mint_config.paused_by,
+            paused_at: mint_config.paused_at,
+            reason: mint_config.pause_reason,
+        })
+    }
+
+    /// Propose a new mint config authority. The pending authority must call
+    /// `accept_authority` once `delay` seconds have passed; until then the
+    /// current authority can cancel.
+    pub fn propose_authority(
+        ctx: Context<UpdateMintConfig>,
+        new_authority: Pubkey,
+        delay: u64,
+    ) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        if new_authority == Pubkey::default() {
+            return Err(ErrorCode::InvalidPendingAuthority.into());
+        }
+
+        mint_config.pending_authority = new_authority;
+        mint_config.authority_transfer_eta = now
+            .checked_add(delay)
+            .ok_or(ErrorCode::InvalidTimelock)?;
+
+        emit!(AuthorityTransferProposed {
+            config: mint_config.key(),
+            authority: mint_config.authority,
+            pending_authority: new_authority,
+            eta: mint_config.authority_transfer_eta,
+        });
+
+        msg!("Proposed mint authority transfer to: {}", new_authority);
+
+        Ok(())
+    }
+
+    /// Complete a proposed authority transfer, signed by the new authority
+    pub fn accept_authority(ctx: Context<AcceptAuthority>) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        if now < mint_config.authority_transfer_eta {
+            return Err(ErrorCode::AuthorityTimelockActive.into());
+        }
+
+        let previous_authority = mint_config.authority;
+        mint_config.authority = mint_config.pending_authority;
+        mint_config.pending_authority = Pubkey::default();
+        mint_config.authority_transfer_eta = 0;
+
+        emit!(AuthorityTransferAccepted {
+            config: mint_config.key(),
+            previous_authority,
+            authority: mint_config.authority,
+        });
+
+        msg!("Updated mint authority to: {}", mint_config.authority);
+
+        Ok(())
+    }
+
+    /// Cancel a pending authority transfer
+    pub fn cancel_authority_transfer(ctx: Context<UpdateMintConfig>) -> Result<()> {
+        let mint_config = &mut ctx.accounts.mint_config;
+
+        if mint_config.pending_authority == Pubkey::default() {
+            return Err(ErrorCode::NoPendingAuthority.into());
+        }
+
+        let pending_authority = mint_config.pending_authority;
+        mint_config.pending_authority = Pubkey::default();
+        mint_config.authority_transfer_eta = 0;
+
+        emit!(AuthorityTransferCancelled {
+            config: mint_config.key(),
+            authority: mint_config.authority,
+            pending_authority,
+        });
+
+        msg!("Cancelled mint authority transfer to: {}", pending_authority);
+
+        Ok(())
+    }
+
+    /// Freeze a token account, e.g. a compromised wallet or a reward-farming
+    /// sybil, using the mint's freeze authority PDA
+    pub fn freeze_account(ctx: Context<ManageFreeze>, reason: FreezeReason) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"mint-authority".as_ref(),
+            mint_key.as_ref(),
+            &[*ctx.bumps.get("mint_authority").unwrap()],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::FreezeAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::freeze_account(cpi_ctx)?;
+
+        emit!(TokenAccountFrozen {
+            token_account: ctx.accounts.token_account.key(),
+            authority: ctx.accounts.authority.key(),
+            reason,
+        });
+
+        msg!("Froze token account {} ({:?})", ctx.accounts.token_account.key(), reason);
+
+        Ok(())
+    }
+
+    /// Thaw a previously frozen token account
+    pub fn thaw_account(ctx: Context<ManageFreeze>, reason: FreezeReason) -> Result<()> {
+        let mint_key = ctx.accounts.mint.key();
+        let seeds = &[
+            b"mint-authority".as_ref(),
+            mint_key.as_ref(),
+            &[*ctx.bumps.get("mint_authority").unwrap()],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = token::ThawAccount {
+            account: ctx.accounts.token_account.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::thaw_account(cpi_ctx)?;
+
+        emit!(TokenAccountThawed {
+            token_account: ctx.accounts.token_account.key(),
+            authority: ctx.accounts.authority.key(),
+            reason,
+        });
+
+        msg!("Thawed token account {} ({:?})", ctx.accounts.token_account.key(), reason);
+
+        Ok(())
+    }
+
+    /// Move the mint and freeze authority of a mint created before authority
+    /// PDAs were per mint from the shared `[b"mint-authority"]` PDA to the
+    /// mint's own `[b"mint-authority", mint]` PDA
+    pub fn migrate_mint_authority(ctx: Context<MigrateMintAuthority>) -> Result<()> {
+        let seeds = &[
+            b"mint-authority".as_ref(),
+            &[*ctx.bumps.get("legacy_mint_authority").unwrap()],
+        ];
+        let signer = &[&seeds[..]];
+        let new_authority = ctx.accounts.mint_authority.key();
+
+        for authority_type in [AuthorityType::MintTokens, AuthorityType::FreezeAccount] {
+            let cpi_accounts = token::SetAuthority {
+                current_authority: ctx.accounts.legacy_mint_authority.to_account_info(),
+                account_or_mint: ctx.accounts.mint.to_account_info(),
+            };
+
+            let cpi_program = ctx.accounts.token_program.to_account_info();
+            let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+            token::set_authority(cpi_ctx, authority_type, Some(new_authority))?;
+        }
+
+        msg!(
+            "Moved authority of mint {} to {}",
+            ctx.accounts.mint.key(),
+            new_authority
+        );
+
         Ok(())
     }
 
-    /// Burn LEARN tokens
-    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64) -> Result<()> {
+    /// Burn LEARN tokens and record the burn under `reason`
+    pub fn burn_tokens(ctx: Context<BurnTokens>, amount: u64, reason: BurnReason) -> Result<()> {
         let cpi_accounts = token::Burn {
             mint: ctx.accounts.mint.to_account_info(),
             from: ctx.accounts.token_account.to_account_info(),
@@ -146,14 +630,46 @@ pub mod solearn_token {
         let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
 
         token::burn(cpi_ctx, amount)?;
+
+        // Update burn accounting
+        let mint_config = &mut ctx.accounts.mint_config;
+        mint_config.total_burned = mint_config
+            .total_burned
+            .checked_add(amount)
+            .ok_or(ErrorCode::Overflow)?;
+        let reason_total = &mut mint_config.burned_by_reason[reason as usize];
+        *reason_total = reason_total.checked_add(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(TokensBurned {
+            mint: ctx.accounts.mint.key(),
+            owner: ctx.accounts.owner.key(),
+            amount,
+            reason,
+            total_burned: mint_config.total_burned,
+        });
         
-        msg!("Burned {} tokens", amount);
+        msg!("Burned {} tokens ({:?})", amount, reason);
         
         Ok(())
     }
+
+    /// Return minted, burned and circulating supply as tracked by the program
+    pub fn get_supply_stats(ctx: Context<GetSupplyStats>) -> Result<SupplyStats> {
+        let mint_config = &ctx.accounts.mint_config;
+        let now = Clock::get()?.unix_timestamp as u64;
+
+        Ok(SupplyStats {
+            total_minted: mint_config.total_minted,
+            total_burned: mint_config.total_burned,
+            circulating_supply: mint_config.total_minted.saturating_sub(mint_config.total_burned),
+            burned_by_reason: mint_config.burned_by_reason,
+            rate_limit_remaining: mint_config.rate_limit_remaining(now),
+        })
+    }
 }
 
 #[derive(Accounts)]
+#[instruction(decimals: u8)]
 pub struct InitializeMint<'info> {
     #[account(
         init,
@@ -174,7 +690,7 @@ pub struct InitializeMint<'info> {
     pub mint: Account<'info, Mint>,
     
     #[account(
-        seeds = [b"mint-authority"],
+        seeds = [b"mint-authority", mint.key().as_ref()],
         bump,
     )]
     /// CHECK: This is the PDA that will have authority over the mint
@@ -189,6 +705,7 @@ pub struct InitializeMint<'info> {
 }
 
 #[derive(Accounts)]
+#[instruction(amount: u64, recipient_wallet: Pubkey)]
 pub struct MintTokens<'info> {
     #[account(
         mut,
@@ -196,7 +713,6 @@ pub struct MintTokens<'info> {
         bump,
         has_one = mint,
         constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
-        constraint = mint_config.authority == authority.key() @ ErrorCode::Unauthorized,
     )]
     pub mint_config: Account<'info, MintConfig>,
     
@@ -204,7 +720,7 @@ pub struct MintTokens<'info> {
     pub mint: Account<'info, Mint>,
     
     #[account(
-        seeds = [b"mint-authority"],
+        seeds = [b"mint-authority", mint.key().as_ref()],
         bump,
     )]
     /// CHECK: This is the PDA that has authority over the mint
@@ -218,52 +734,413 @@ pub struct MintTokens<'info> {
     pub token_account: Account<'info, TokenAccount>,
 
     /// CHECK: This is the recipient of the tokens
+    #[account(address = recipient_wallet @ ErrorCode::InvalidRecipient)]
     pub recipient: UncheckedAccount<'info>,
     
     #[account(
-        constraint = authority.key() == mint_config.authority @ ErrorCode::Unauthorized,
+        mut,
+        seeds = [b"minter-allowance", mint.key().as_ref(), authority.key().as_ref()],
+        bump = minter_allowance.bump,
+        has_one = allocation_bucket @ ErrorCode::InvalidAllocationBucket,
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    
+    #[account(
+        mut,
+        seeds = [b"allocation-bucket", mint.key().as_ref(), allocation_bucket.name.as_bytes()],
+        bump = allocation_bucket.bump,
+    )]
+    pub allocation_bucket: Account<'info, AllocationBucket>,
+    
+    #[account(
+        mut,
+        seeds = [b"emission-schedule", mint.key().as_ref()],
+        bump = emission_schedule.bump,
     )]
+    pub emission_schedule: Option<Account<'info, EmissionSchedule>>,
+    
     pub authority: Signer<'info>,
     
     pub token_program: Program<'info, Token>,
 }
 
 #[derive(Accounts)]
-pub struct UpdateMintConfig<'info> {
+pub struct InitializeEmissionSchedule<'info> {
     #[account(
         mut,
         seeds = [b"mint-config", mint.key().as_ref()],
         bump,
         has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
         constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
-        constraint = mint_config.authority == authority.key() @ ErrorCode::Unauthorized,
     )]
     pub mint_config: Account<'info, MintConfig>,
     
     pub mint: Account<'info, Mint>,
     
     #[account(
-        constraint = authority.key() == mint_config.authority @ ErrorCode::Unauthorized,
+        init,
+        seeds = [b"emission-schedule", mint.key().as_ref()],
+        bump,
+        payer = authority,
+        space = 8 + EmissionSchedule::LEN
     )]
+    pub emission_schedule: Account<'info, EmissionSchedule>,
+    
+    #[account(mut)]
     pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
-pub struct BurnTokens<'info> {
-    #[account(mut)]
+pub struct UpdateEmissionSchedule<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
     pub mint: Account<'info, Mint>,
     
     #[account(
         mut,
-        constraint = token_account.mint == mint.key(),
-        constraint = token_account.owner == owner.key(),
-        constraint = token_account.amount >= amount,
+        seeds = [b"emission-schedule", mint.key().as_ref()],
+        bump = emission_schedule.bump,
     )]
-    pub token_account: Account<'info, TokenAccount>,
+    pub emission_schedule: Account<'info, EmissionSchedule>,
     
-    pub owner: Signer<'info>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(name: String)]
+pub struct CreateAllocationBucket<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
     
-    pub token_program: Program<'info, Token>,
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        init,
+        seeds = [b"allocation-bucket", mint.key().as_ref(), name.as_bytes()],
+        bump,
+        payer = authority,
+        space = 8 + AllocationBucket::LEN
+    )]
+    pub allocation_bucket: Account<'info, AllocationBucket>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAllocationBucket<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"allocation-bucket", mint.key().as_ref(), allocation_bucket.name.as_bytes()],
+        bump = allocation_bucket.bump,
+    )]
+    pub allocation_bucket: Account<'info, AllocationBucket>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetAllocationSummary<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+#[instruction(minter: Pubkey)]
+pub struct GrantMinterAllowance<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"allocation-bucket", mint.key().as_ref(), allocation_bucket.name.as_bytes()],
+        bump = allocation_bucket.bump,
+    )]
+    pub allocation_bucket: Account<'info, AllocationBucket>,
+    
+    #[account(
+        init,
+        seeds = [b"minter-allowance", mint.key().as_ref(), minter.as_ref()],
+        bump,
+        payer = authority,
+        space = 8 + MinterAllowance::LEN
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMinterAllowance<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        seeds = [b"minter-allowance", mint.key().as_ref(), minter_allowance.minter.as_ref()],
+        bump = minter_allowance.bump,
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeMinterAllowance<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"minter-allowance", mint.key().as_ref(), minter_allowance.minter.as_ref()],
+        bump = minter_allowance.bump,
+    )]
+    pub minter_allowance: Account<'info, MinterAllowance>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMintConfig<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+        constraint = mint_config.authority == authority.key() @ ErrorCode::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        constraint = authority.key() == mint_config.authority @ ErrorCode::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct EmergencyPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+        constraint = mint_config.can_pause(&guardian.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    pub guardian: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct GetPauseState<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = pending_authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    pub pending_authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateMintAuthority<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        has_one = authority @ ErrorCode::Unauthorized,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    #[account(
+        mut,
+        constraint = mint.mint_authority == COption::Some(legacy_mint_authority.key()) @ ErrorCode::MintAuthorityAlreadyMigrated,
+    )]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"mint-authority"],
+        bump,
+    )]
+    /// CHECK: The shared mint authority PDA used before authorities were per mint
+    pub legacy_mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"mint-authority", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: This is the PDA that will have authority over the mint
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ManageFreeze<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        seeds = [b"mint-authority", mint.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: This is the PDA that is the mint's freeze authority
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key(),
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        constraint = mint_config.can_freeze(&authority.key()) @ ErrorCode::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64)]
+pub struct BurnTokens<'info> {
+    #[account(
+        mut,
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+    
+    #[account(
+        mut,
+        constraint = token_account.mint == mint.key(),
+        constraint = token_account.owner == owner.key(),
+        constraint = token_account.amount >= amount,
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+    
+    pub owner: Signer<'info>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetSupplyStats<'info> {
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        has_one = mint,
+        constraint = mint_config.is_initialized @ ErrorCode::MintNotInitialized,
+    )]
+    pub mint_config: Account<'info, MintConfig>,
+    
+    pub mint: Account<'info, Mint>,
 }
 
 #[account]
@@ -276,12 +1153,405 @@ pub struct MintConfig {
     pub total_minted: u64,           // 8 bytes
     pub decimals: u8,                // 1 byte
     pub paused: bool,                // 1 byte
+    pub paused_by: Pubkey,           // 32 bytes, default while not paused
+    pub paused_at: u64,              // 8 bytes
+    pub pause_reason: PauseReason,   // 1 byte
+    pub guardians: Vec<Pubkey>,      // 4 + 32 * MAX_GUARDIANS bytes
     pub last_mint_timestamp: u64,    // 8 bytes
-    pub mint_cooldown: u64,          // 8 bytes
+    pub rate_limit_amount: u64,      // 8 bytes, 0 disables the rate limit
+    pub rate_limit_window: u64,      // 8 bytes
+    pub rate_limit_available: u64,   // 8 bytes
+    pub rate_limit_updated_at: u64,  // 8 bytes
+    pub emission_minter: Pubkey,     // 32 bytes, default when no emission schedule
+    pub total_allocated: u64,        // 8 bytes, sum of allocation bucket caps
+    pub total_burned: u64,           // 8 bytes
+    pub burned_by_reason: [u64; BurnReason::COUNT], // 8 * BurnReason::COUNT bytes
+    pub compliance_authority: Pubkey, // 32 bytes, default when unset
+    pub pending_authority: Pubkey,   // 32 bytes, default when no transfer is pending
+    pub authority_transfer_eta: u64, // 8 bytes
 }
 
 impl MintConfig {
-    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 1 + 1 + 8 + 8;
+    pub const MAX_GUARDIANS: usize = 5;
+    pub const LEN: usize = 1 + 32 + 32 + 8 + 8 + 1 + 1 + 32 + 8 + 1
+        + (4 + 32 * Self::MAX_GUARDIANS) + 8 + 8 + 8 + 8 + 8 + 32 + 8 + 8
+        + 8 * BurnReason::COUNT + 32 + 32 + 8;
+
+    /// Whether `key` may pause minting: the authority or any guardian
+    pub fn can_pause(&self, key: &Pubkey) -> bool {
+        self.authority == *key || self.guardians.contains(key)
+    }
+
+    /// Mark minting paused by `paused_by`
+    pub fn record_pause(&mut self, paused_by: Pubkey, reason: PauseReason, now: u64) {
+        self.paused = true;
+        self.paused_by = paused_by;
+        self.paused_at = now;
+        self.pause_reason = reason;
+    }
+
+    /// Clear the pause state
+    pub fn record_unpause(&mut self) {
+        self.paused = false;
+        self.paused_by = Pubkey::default();
+        self.paused_at = 0;
+        self.pause_reason = PauseReason::default();
+    }
+
+    /// Whether `key` may freeze and thaw token accounts of this mint
+    pub fn can_freeze(&self, key: &Pubkey) -> bool {
+        self.authority == *key
+            || (self.compliance_authority != Pubkey::default() && self.compliance_authority == *key)
+    }
+
+    /// Token-bucket rate limit: the bucket holds up to `rate_limit_amount`
+    /// tokens and refills continuously over `rate_limit_window` seconds.
+    /// Consumes `amount` or fails, logging the remaining capacity.
+    pub fn consume_rate_limit(&mut self, amount: u64, now: u64) -> Result<()> {
+        if self.rate_limit_amount == 0 {
+            return Ok(());
+        }
+
+        // The timestamp only advances when at least one token was refilled
+        // so that slow trickles are not rounded away
+        if let Some(available) = self.refilled_rate_limit(now) {
+            self.rate_limit_available = available;
+            self.rate_limit_updated_at = now;
+        }
+
+        if amount > self.rate_limit_available {
+            msg!(
+                "Mint rate limit exceeded: requested {}, remaining capacity {}",
+                amount,
+                self.rate_limit_available
+            );
+            return Err(ErrorCode::MintRateLimitExceeded.into());
+        }
+        self.rate_limit_available -= amount;
+
+        Ok(())
+    }
+
+    /// Tokens that can be minted at `now` under the rate limit, `u64::MAX`
+    /// when no rate limit is set
+    pub fn rate_limit_remaining(&self, now: u64) -> u64 {
+        if self.rate_limit_amount == 0 {
+            return u64::MAX;
+        }
+        self.refilled_rate_limit(now).unwrap_or(self.rate_limit_available)
+    }
+
+    /// Bucket level after refilling for the time elapsed since the last
+    /// update, or `None` when less than one token has accrued
+    fn refilled_rate_limit(&self, now: u64) -> Option<u64> {
+        let elapsed = now.saturating_sub(self.rate_limit_updated_at);
+        let refill = (u128::from(elapsed) * u128::from(self.rate_limit_amount)
+            / u128::from(self.rate_limit_window))
+        .min(u128::from(u64::MAX)) as u64;
+        if refill == 0 {
+            return None;
+        }
+        Some(
+            self.rate_limit_available
+                .saturating_add(refill)
+                .min(self.rate_limit_amount),
+        )
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct AllocationBucket {
+    pub mint: Pubkey,                // 32 bytes
+    pub name: String,                // 4 + 32 = 36 bytes
+    pub cap: u64,                    // 8 bytes
+    pub minted: u64,                 // 8 bytes
+    pub bump: u8,                    // 1 byte
+}
+
+impl AllocationBucket {
+    pub const MAX_NAME_LEN: usize = 32;
+    pub const LEN: usize = 32 + (4 + Self::MAX_NAME_LEN) + 8 + 8 + 1;
+
+    /// Check `amount` against the bucket cap and record it
+    pub fn record_mint(&mut self, amount: u64) -> Result<()> {
+        self.minted = self
+            .minted
+            .checked_add(amount)
+            .filter(|minted| *minted <= self.cap)
+            .ok_or(ErrorCode::BucketCapExceeded)?;
+
+        Ok(())
+    }
+}
+
+/// Return data of `get_allocation_summary`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AllocationSummary {
+    pub supply_cap: u64,
+    pub total_allocated: u64,
+    pub total_minted: u64,
+    pub buckets: Vec<AllocationBucketSummary>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct AllocationBucketSummary {
+    pub name: String,
+    pub cap: u64,
+    pub minted: u64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct EmissionSchedule {
+    pub mint: Pubkey,                // 32 bytes
+    pub minter: Pubkey,              // 32 bytes
+    pub genesis_timestamp: u64,      // 8 bytes
+    pub yearly_emissions: [u64; Self::SCHEDULED_YEARS], // 8 * SCHEDULED_YEARS bytes
+    pub unused_emission_policy: UnusedEmissionPolicy, // 1 byte
+    pub current_year: u64,           // 8 bytes
+    pub current_year_minted: u64,    // 8 bytes
+    pub carried_over: u64,           // 8 bytes
+    pub bump: u8,                    // 1 byte
+}
+
+impl EmissionSchedule {
+    /// Years 1 through 10 of the tokenomics table plus the perpetual tail rate
+    pub const SCHEDULED_YEARS: usize = 11;
+    pub const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+    pub const LEN: usize = 32 + 32 + 8 + 8 * Self::SCHEDULED_YEARS + 1 + 8 + 8 + 8 + 1;
+
+    /// Emission allowance for a zero-based year since genesis
+    pub fn allowance_for_year(&self, year: u64) -> u64 {
+        let index = usize::try_from(year)
+            .unwrap_or(usize::MAX)
+            .min(Self::SCHEDULED_YEARS - 1);
+        self.yearly_emissions[index]
+    }
+
+    /// Budget for the current year, including any rolled-over emission
+    pub fn current_year_budget(&self) -> u64 {
+        self.allowance_for_year(self.current_year)
+            .saturating_add(self.carried_over)
+    }
+
+    /// Zero-based year since genesis at `now`, `None` before genesis
+    pub fn year_at(&self, now: u64) -> Option<u64> {
+        now.checked_sub(self.genesis_timestamp)
+            .map(|elapsed| elapsed / Self::SECONDS_PER_YEAR)
+    }
+
+    /// Move the schedule forward to `year`, rolling over or expiring the
+    /// unused emission of every year passed on the way
+    fn advance_to_year(&mut self, year: u64) {
+        while self.current_year < year {
+            self.carried_over = match self.unused_emission_policy {
+                UnusedEmissionPolicy::Rollover => self
+                    .current_year_budget()
+                    .saturating_sub(self.current_year_minted),
+                UnusedEmissionPolicy::Expire => 0,
+            };
+            self.current_year += 1;
+            self.current_year_minted = 0;
+        }
+    }
+
+    /// Check `amount` against the current year's budget and record it
+    pub fn record_emission(&mut self, amount: u64, now: u64) -> Result<()> {
+        let year = self.year_at(now).ok_or(ErrorCode::EmissionNotStarted)?;
+        self.advance_to_year(year);
+
+        self.current_year_minted = self
+            .current_year_minted
+            .checked_add(amount)
+            .filter(|minted| *minted <= self.current_year_budget())
+            .ok_or(ErrorCode::YearlyEmissionExceeded)?;
+
+        Ok(())
+    }
+}
+
+/// What happens to emission allowance left unused at the end of a year
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnusedEmissionPolicy {
+    #[default]
+    Expire,
+    Rollover,
+}
+
+/// Why tokens were burned, following the burn mechanisms in the tokenomics
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BurnReason {
+    PlatformFee,
+    ChallengeFee,
+    UnclaimedRewards,
+    Governance,
+}
+
+impl BurnReason {
+    pub const COUNT: usize = 4;
+}
+
+/// Return data of `get_supply_stats`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct SupplyStats {
+    pub total_minted: u64,
+    pub total_burned: u64,
+    pub circulating_supply: u64,
+    pub burned_by_reason: [u64; BurnReason::COUNT],
+    /// Tokens that can be minted right now under the global rate limit,
+    /// `u64::MAX` when no rate limit is set
+    pub rate_limit_remaining: u64,
+}
+
+#[event]
+pub struct TokensBurned {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub reason: BurnReason,
+    pub total_burned: u64,
+}
+
+/// Why a token account was frozen or thawed
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FreezeReason {
+    CompromisedWallet,
+    RewardFarming,
+    Compliance,
+    Resolved,
+}
+
+#[event]
+pub struct TokenAccountFrozen {
+    pub token_account: Pubkey,
+    pub authority: Pubkey,
+    pub reason: FreezeReason,
+}
+
+#[event]
+pub struct TokenAccountThawed {
+    pub token_account: Pubkey,
+    pub authority: Pubkey,
+    pub reason: FreezeReason,
+}
+
+/// Why minting was paused
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PauseReason {
+    /// Paused by the authority through `update_mint_config`
+    #[default]
+    Administrative,
+    SecurityIncident,
+    AnomalousMinting,
+    Upgrade,
+    Other,
+}
+
+/// Return data of `get_pause_state`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug)]
+pub struct PauseState {
+    pub paused: bool,
+    pub paused_by: Pubkey,
+    pub paused_at: u64,
+    pub reason: PauseReason,
+}
+
+#[event]
+pub struct MintingPaused {
+    pub config: Pubkey,
+    pub paused_by: Pubkey,
+    pub reason: PauseReason,
+    pub paused_at: u64,
+}
+
+#[event]
+pub struct MintingUnpaused {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferProposed {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+    pub eta: u64,
+}
+
+#[event]
+pub struct AuthorityTransferAccepted {
+    pub config: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct AuthorityTransferCancelled {
+    pub config: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// Global minting rate limit: at most `amount` tokens per `window` seconds.
+/// An `amount` of zero disables the limit.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug)]
+pub struct RateLimit {
+    pub amount: u64,
+    pub window: u64,
+}
+
+#[account]
+#[derive(Default)]
+pub struct MinterAllowance {
+    pub mint: Pubkey,                // 32 bytes
+    pub minter: Pubkey,              // 32 bytes
+    pub allocation_bucket: Pubkey,   // 32 bytes
+    pub quota: u64,                  // 8 bytes
+    pub minted: u64,                 // 8 bytes
+    pub window_duration: u64,        // 8 bytes, 0 disables the rate window
+    pub window_limit: u64,           // 8 bytes
+    pub window_start: u64,           // 8 bytes
+    pub window_minted: u64,          // 8 bytes
+    pub bump: u8,                    // 1 byte
+}
+
+impl MinterAllowance {
+    pub const LEN: usize = 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    /// Check `amount` against the quota and current rate window, then record it
+    pub fn record_mint(&mut self, amount: u64, now: u64) -> Result<()> {
+        let minted = self
+            .minted
+            .checked_add(amount)
+            .filter(|minted| *minted <= self.quota)
+            .ok_or(ErrorCode::MinterQuotaExceeded)?;
+
+        if self.window_duration > 0 {
+            // Start a fresh window once the current one has elapsed
+            if now >= self.window_start.saturating_add(self.window_duration) {
+                self.window_start = now;
+                self.window_minted = 0;
+            }
+            self.window_minted = self
+                .window_minted
+                .checked_add(amount)
+                .filter(|window_minted| *window_minted <= self.window_limit)
+                .ok_or(ErrorCode::MinterRateLimitExceeded)?;
+        }
+
+        self.minted = minted;
+
+        Ok(())
+    }
+}
+
+/// Whether `key` controls minting of `mint`: its SPL mint authority or, for
+/// a mint managed by this program, the authority of its MintConfig. Callers
+/// must only pass the MintConfig PDA of `mint`.
+pub fn controls_mint(mint: &Mint, mint_config: Option<&MintConfig>, key: &Pubkey) -> bool {
+    mint.mint_authority == COption::Some(*key)
+        || matches!(mint_config, Some(config) if config.authority == *key)
 }
 
 #[error_code]
@@ -301,6 +1571,66 @@ pub enum ErrorCode {
     #[msg("Minting is paused")]
     MintingPaused,
     
-    #[msg("Mint cooldown period not met")]
-    MintCooldownNotMet,
+    #[msg("Mint rate limit exceeded")]
+    MintRateLimitExceeded,
+    
+    #[msg("Invalid rate limit window")]
+    InvalidRateLimit,
+    
+    #[msg("This minter must provide the emission schedule")]
+    EmissionScheduleRequired,
+    
+    #[msg("Emission schedule has not started yet")]
+    EmissionNotStarted,
+    
+    #[msg("Yearly emission allowance exceeded")]
+    YearlyEmissionExceeded,
+    
+    #[msg("Invalid allocation bucket name")]
+    InvalidBucketName,
+    
+    #[msg("Allocation bucket caps would exceed the supply cap")]
+    AllocationExceedsSupplyCap,
+    
+    #[msg("Bucket cap cannot be below the amount already minted")]
+    InvalidBucketCap,
+    
+    #[msg("Allocation bucket cap exceeded")]
+    BucketCapExceeded,
+    
+    #[msg("Invalid allocation bucket")]
+    InvalidAllocationBucket,
+    
+    #[msg("Mint authority has already been migrated")]
+    MintAuthorityAlreadyMigrated,
+    
+    #[msg("Invalid pending authority")]
+    InvalidPendingAuthority,
+    
+    #[msg("Invalid timelock delay")]
+    InvalidTimelock,
+    
+    #[msg("No authority transfer is pending")]
+    NoPendingAuthority,
+    
+    #[msg("Authority transfer timelock has not elapsed")]
+    AuthorityTimelockActive,
+    
+    #[msg("Minter allowance quota exceeded")]
+    MinterQuotaExceeded,
+    
+    #[msg("Minter rate window limit exceeded")]
+    MinterRateLimitExceeded,
+    
+    #[msg("Allowance quota cannot be below the amount already minted")]
+    InvalidAllowanceQuota,
+    
+    #[msg("Too many guardians")]
+    TooManyGuardians,
+    
+    #[msg("Recipient account does not match the recipient wallet")]
+    InvalidRecipient,
+    
+    #[msg("Arithmetic overflow")]
+    Overflow,
 } 
\ No newline at end of file
@@ -0,0 +1,548 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_token::{
+    BurnReason, EmissionSchedule, MintConfig, MinterAllowance, RateLimit, UnusedEmissionPolicy,
+};
+
+const SUPPLY_CAP: u64 = 1_000_000_000;
+const BUCKET_NAME: &str = "learning-mining";
+const BUCKET_CAP: u64 = 100_000_000;
+const QUOTA: u64 = 10_000_000;
+const YEAR: i64 = EmissionSchedule::SECONDS_PER_YEAR as i64;
+
+struct Fixture {
+    context: ProgramTestContext,
+    authority: Keypair,
+    minter: Keypair,
+    recipient: Keypair,
+    mint: Pubkey,
+    token_account: Pubkey,
+    now: i64,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+impl Fixture {
+    /// Send a transaction at the fixture's current time
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = self.now;
+        self.context.set_sysvar(&clock);
+
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            all_signers.as_slice(),
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    async fn account<T: AccountDeserialize>(&mut self, address: Pubkey) -> T {
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        T::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    async fn balance(&mut self) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(self.token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    fn address(&self, seeds: &[&[u8]]) -> Pubkey {
+        Pubkey::find_program_address(seeds, &solearn_token::id()).0
+    }
+
+    fn mint_config(&self) -> Pubkey {
+        self.address(&[b"mint-config", self.mint.as_ref()])
+    }
+
+    fn mint_authority(&self) -> Pubkey {
+        self.address(&[b"mint-authority", self.mint.as_ref()])
+    }
+
+    fn allocation_bucket(&self) -> Pubkey {
+        self.address(&[b"allocation-bucket", self.mint.as_ref(), BUCKET_NAME.as_bytes()])
+    }
+
+    fn minter_allowance(&self) -> Pubkey {
+        self.address(&[b"minter-allowance", self.mint.as_ref(), self.minter.pubkey().as_ref()])
+    }
+
+    fn emission_schedule(&self) -> Pubkey {
+        self.address(&[b"emission-schedule", self.mint.as_ref()])
+    }
+
+    /// Mint `amount` to the recipient as the fixture's minter, passing the
+    /// emission schedule when `with_schedule` is set
+    async fn mint_tokens(&mut self, amount: u64, with_schedule: bool) -> Result<(), BanksClientError> {
+        let minter = self.minter.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::MintTokens {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                mint_authority: self.mint_authority(),
+                token_account: self.token_account,
+                recipient: self.recipient.pubkey(),
+                minter_allowance: self.minter_allowance(),
+                allocation_bucket: self.allocation_bucket(),
+                emission_schedule: with_schedule.then(|| self.emission_schedule()),
+                authority: minter.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::MintTokens {
+                amount,
+                recipient_wallet: self.recipient.pubkey(),
+            }
+            .data(),
+        };
+        self.process(&[instruction], &minter, &[]).await
+    }
+
+    /// Burn `amount` from the recipient's token account
+    async fn burn_tokens(&mut self, amount: u64, reason: BurnReason) -> Result<(), BanksClientError> {
+        let recipient = self.recipient.insecure_clone();
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::BurnTokens {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                token_account: self.token_account,
+                owner: recipient.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::BurnTokens { amount, reason }.data(),
+        };
+        self.process(&[instruction], &authority, &[&recipient]).await
+    }
+
+    async fn set_rate_limit(&mut self, amount: u64, window: u64) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::UpdateMintConfig {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                authority: authority.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::UpdateMintConfig {
+                new_supply_cap: None,
+                new_rate_limit: Some(RateLimit { amount, window }),
+                pause_minting: None,
+                new_compliance_authority: None,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+
+    async fn update_minter_allowance(
+        &mut self,
+        quota: Option<u64>,
+        window_duration: Option<u64>,
+        window_limit: Option<u64>,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::UpdateMinterAllowance {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                minter_allowance: self.minter_allowance(),
+                authority: authority.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::UpdateMinterAllowance {
+                quota,
+                window_duration,
+                window_limit,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+
+    /// Put the minter on an emission schedule starting now
+    async fn initialize_emission_schedule(
+        &mut self,
+        yearly_emissions: [u64; EmissionSchedule::SCHEDULED_YEARS],
+        unused_emission_policy: UnusedEmissionPolicy,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::InitializeEmissionSchedule {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                emission_schedule: self.emission_schedule(),
+                authority: authority.pubkey(),
+                system_program: system_program::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::InitializeEmissionSchedule {
+                minter: self.minter.pubkey(),
+                genesis_timestamp: self.now as u64,
+                yearly_emissions,
+                unused_emission_policy,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+
+    async fn update_emission_policy(
+        &mut self,
+        unused_emission_policy: UnusedEmissionPolicy,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_token::id(),
+            accounts: solearn_token::accounts::UpdateEmissionSchedule {
+                mint_config: self.mint_config(),
+                mint: self.mint,
+                emission_schedule: self.emission_schedule(),
+                authority: authority.pubkey(),
+            }
+            .to_account_metas(None),
+            data: solearn_token::instruction::UpdateEmissionPolicy {
+                unused_emission_policy,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+}
+
+/// Start a validator with an initialized LEARN mint, an allocation bucket,
+/// a minter allowance drawing from it and an empty recipient token account
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_token",
+        solearn_token::id(),
+        processor!(solearn_token::entry),
+    );
+
+    let authority = Keypair::new();
+    let minter = Keypair::new();
+    program_test.add_account(authority.pubkey(), funded_account());
+    program_test.add_account(minter.pubkey(), funded_account());
+
+    let context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    let mint = Keypair::new();
+    let token_account = Keypair::new();
+    let mut fixture = Fixture {
+        context,
+        authority: authority.insecure_clone(),
+        minter: minter.insecure_clone(),
+        recipient: Keypair::new(),
+        mint: mint.pubkey(),
+        token_account: token_account.pubkey(),
+        now,
+    };
+
+    let initialize_mint = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::InitializeMint {
+            mint_config: fixture.mint_config(),
+            mint: mint.pubkey(),
+            mint_authority: fixture.mint_authority(),
+            authority: authority.pubkey(),
+            system_program: system_program::id(),
+            token_program: spl_token::id(),
+            rent: sysvar::rent::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::InitializeMint {
+            decimals: 6,
+            supply_cap: SUPPLY_CAP,
+        }
+        .data(),
+    };
+    let create_bucket = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::CreateAllocationBucket {
+            mint_config: fixture.mint_config(),
+            mint: mint.pubkey(),
+            allocation_bucket: fixture.allocation_bucket(),
+            authority: authority.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::CreateAllocationBucket {
+            name: BUCKET_NAME.to_string(),
+            cap: BUCKET_CAP,
+        }
+        .data(),
+    };
+    let grant_allowance = Instruction {
+        program_id: solearn_token::id(),
+        accounts: solearn_token::accounts::GrantMinterAllowance {
+            mint_config: fixture.mint_config(),
+            mint: mint.pubkey(),
+            allocation_bucket: fixture.allocation_bucket(),
+            minter_allowance: fixture.minter_allowance(),
+            authority: authority.pubkey(),
+            system_program: system_program::id(),
+        }
+        .to_account_metas(None),
+        data: solearn_token::instruction::GrantMinterAllowance {
+            minter: minter.pubkey(),
+            quota: QUOTA,
+            window_duration: 0,
+            window_limit: 0,
+        }
+        .data(),
+    };
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    let instructions = [
+        initialize_mint,
+        create_bucket,
+        grant_allowance,
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &token_account.pubkey(),
+            rent.minimum_balance(spl_token::state::Account::LEN),
+            spl_token::state::Account::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_account(
+            &spl_token::id(),
+            &token_account.pubkey(),
+            &mint.pubkey(),
+            &fixture.recipient.pubkey(),
+        )
+        .unwrap(),
+    ];
+    fixture
+        .process(&instructions, &authority, &[&mint, &token_account])
+        .await
+        .unwrap();
+
+    fixture
+}
+
+#[tokio::test]
+async fn rate_limit_bucket_refills_over_its_window() {
+    let mut fixture = setup().await;
+    fixture.set_rate_limit(1_000, 100).await.unwrap();
+
+    // The bucket starts full and empties as tokens are minted
+    fixture.mint_tokens(1_000, false).await.unwrap();
+    assert!(fixture.mint_tokens(1, false).await.is_err());
+
+    // A tenth of the window refills a tenth of the bucket
+    fixture.now += 10;
+    assert!(fixture.mint_tokens(101, false).await.is_err());
+    fixture.mint_tokens(100, false).await.unwrap();
+    assert!(fixture.mint_tokens(1, false).await.is_err());
+
+    // Refills never overflow the bucket, however long it sat idle
+    fixture.now += 1_000;
+    let mint_config = fixture.mint_config();
+    let config: MintConfig = fixture.account(mint_config).await;
+    assert_eq!(config.rate_limit_remaining(fixture.now as u64), 1_000);
+    assert!(fixture.mint_tokens(1_001, false).await.is_err());
+    fixture.mint_tokens(1_000, false).await.unwrap();
+
+    let config: MintConfig = fixture.account(mint_config).await;
+    assert_eq!(config.rate_limit_available, 0);
+    assert_eq!(config.total_minted, 2_100);
+    assert_eq!(fixture.balance().await, 2_100);
+
+    // Removing the rate limit lifts the cap entirely
+    fixture.set_rate_limit(0, 0).await.unwrap();
+    fixture.mint_tokens(5_000, false).await.unwrap();
+}
+
+#[tokio::test]
+async fn unused_emission_rolls_over_at_the_year_boundary() {
+    let mut fixture = setup().await;
+    let genesis = fixture.now;
+    let mut yearly_emissions = [50; EmissionSchedule::SCHEDULED_YEARS];
+    yearly_emissions[0] = 100;
+    yearly_emissions[EmissionSchedule::SCHEDULED_YEARS - 1] = 10;
+    fixture
+        .initialize_emission_schedule(yearly_emissions, UnusedEmissionPolicy::Rollover)
+        .await
+        .unwrap();
+
+    // The emission minter must pass its schedule
+    assert!(fixture.mint_tokens(10, false).await.is_err());
+
+    // Year 0 allows 100 tokens, up to the last second of the year
+    fixture.mint_tokens(60, true).await.unwrap();
+    fixture.now = genesis + YEAR - 1;
+    assert!(fixture.mint_tokens(41, true).await.is_err());
+
+    // The unused 40 roll over into year 1 on top of its 50
+    fixture.now = genesis + YEAR;
+    assert!(fixture.mint_tokens(91, true).await.is_err());
+    fixture.mint_tokens(90, true).await.unwrap();
+    let emission_schedule = fixture.emission_schedule();
+    let schedule: EmissionSchedule = fixture.account(emission_schedule).await;
+    assert_eq!(schedule.current_year, 1);
+    assert_eq!(schedule.carried_over, 40);
+    assert_eq!(schedule.current_year_minted, 90);
+
+    // Skipping years rolls each one over in turn: year 3 gets its own 50
+    // plus year 2's unused 50, and years past the table use its last entry
+    fixture.now = genesis + 3 * YEAR;
+    fixture.mint_tokens(100, true).await.unwrap();
+    fixture.now = genesis + 20 * YEAR;
+    let schedule: EmissionSchedule = fixture.account(emission_schedule).await;
+    assert_eq!(schedule.allowance_for_year(20), 10);
+    let carried = 6 * 50 + 10 * 10;
+    assert!(fixture.mint_tokens(carried + 11, true).await.is_err());
+    fixture.mint_tokens(carried + 10, true).await.unwrap();
+    assert_eq!(fixture.balance().await, 60 + 90 + 100 + carried + 10);
+}
+
+#[tokio::test]
+async fn unused_emission_expires_at_the_year_boundary() {
+    let mut fixture = setup().await;
+    let genesis = fixture.now;
+    fixture
+        .initialize_emission_schedule([100; EmissionSchedule::SCHEDULED_YEARS], UnusedEmissionPolicy::Expire)
+        .await
+        .unwrap();
+
+    fixture.mint_tokens(30, true).await.unwrap();
+
+    // Only the new year's allowance is available after the boundary
+    fixture.now = genesis + YEAR;
+    assert!(fixture.mint_tokens(101, true).await.is_err());
+    fixture.mint_tokens(100, true).await.unwrap();
+    let emission_schedule = fixture.emission_schedule();
+    let schedule: EmissionSchedule = fixture.account(emission_schedule).await;
+    assert_eq!(schedule.carried_over, 0);
+    assert!(fixture.mint_tokens(1, true).await.is_err());
+}
+
+#[tokio::test]
+async fn emission_policy_changes_apply_from_the_next_boundary() {
+    let mut fixture = setup().await;
+    let genesis = fixture.now;
+    fixture
+        .initialize_emission_schedule([100; EmissionSchedule::SCHEDULED_YEARS], UnusedEmissionPolicy::Rollover)
+        .await
+        .unwrap();
+    fixture.mint_tokens(60, true).await.unwrap();
+
+    // Year 0 ended under the rollover policy, so its unused 40 carry over
+    // even though the policy changes before anything is minted in year 1
+    fixture.now = genesis + YEAR + 10;
+    fixture
+        .update_emission_policy(UnusedEmissionPolicy::Expire)
+        .await
+        .unwrap();
+    let emission_schedule = fixture.emission_schedule();
+    let schedule: EmissionSchedule = fixture.account(emission_schedule).await;
+    assert_eq!(schedule.current_year, 1);
+    assert_eq!(schedule.carried_over, 40);
+    fixture.mint_tokens(100, true).await.unwrap();
+
+    // Year 1 ends under the new policy and its unused 40 expire
+    fixture.now = genesis + 2 * YEAR;
+    assert!(fixture.mint_tokens(101, true).await.is_err());
+    fixture.mint_tokens(100, true).await.unwrap();
+}
+
+#[tokio::test]
+async fn minter_allowance_is_exhausted_at_its_quota() {
+    let mut fixture = setup().await;
+
+    fixture.mint_tokens(QUOTA - 100, false).await.unwrap();
+    assert!(fixture.mint_tokens(101, false).await.is_err());
+    fixture.mint_tokens(100, false).await.unwrap();
+    assert!(fixture.mint_tokens(1, false).await.is_err());
+
+    let minter_allowance = fixture.minter_allowance();
+    let allowance: MinterAllowance = fixture.account(minter_allowance).await;
+    assert_eq!(allowance.minted, QUOTA);
+
+    // The quota cannot drop below what was minted, but can be raised
+    assert!(fixture
+        .update_minter_allowance(Some(QUOTA - 1), None, None)
+        .await
+        .is_err());
+    fixture
+        .update_minter_allowance(Some(QUOTA + 1_000), Some(100), Some(400))
+        .await
+        .unwrap();
+
+    // The rate window caps minting within each window of the new quota
+    fixture.mint_tokens(400, false).await.unwrap();
+    assert!(fixture.mint_tokens(1, false).await.is_err());
+    fixture.now += 100;
+    assert!(fixture.mint_tokens(401, false).await.is_err());
+    fixture.mint_tokens(400, false).await.unwrap();
+    fixture.now += 100;
+    assert!(fixture.mint_tokens(201, false).await.is_err());
+    fixture.mint_tokens(200, false).await.unwrap();
+
+    let allowance: MinterAllowance = fixture.account(minter_allowance).await;
+    assert_eq!(allowance.minted, QUOTA + 1_000);
+    assert_eq!(fixture.balance().await, QUOTA + 1_000);
+}
+
+#[tokio::test]
+async fn burns_are_tracked_by_reason() {
+    let mut fixture = setup().await;
+    fixture.mint_tokens(1_000, false).await.unwrap();
+
+    fixture.burn_tokens(300, BurnReason::PlatformFee).await.unwrap();
+    fixture.burn_tokens(200, BurnReason::Governance).await.unwrap();
+    fixture.burn_tokens(100, BurnReason::PlatformFee).await.unwrap();
+    assert!(fixture.burn_tokens(401, BurnReason::ChallengeFee).await.is_err());
+
+    let mint_config = fixture.mint_config();
+    let config: MintConfig = fixture.account(mint_config).await;
+    assert_eq!(config.total_burned, 600);
+    assert_eq!(config.burned_by_reason[BurnReason::PlatformFee as usize], 400);
+    assert_eq!(config.burned_by_reason[BurnReason::ChallengeFee as usize], 0);
+    assert_eq!(config.burned_by_reason[BurnReason::Governance as usize], 200);
+    assert_eq!(fixture.balance().await, 400);
+}
@@ -0,0 +1,347 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use std::convert::TryFrom;
+
+declare_id!("LearnVestingProgram111111111111111111111111");
+
+/// Team & Advisors: 4-year linear vesting with a 1-year cliff
+pub const TEAM_VESTING_DURATION: i64 = 4 * 365 * 24 * 60 * 60;
+pub const TEAM_CLIFF_DURATION: i64 = 365 * 24 * 60 * 60;
+
+/// Strategic Partners & Investors: 2-year linear vesting with a 6-month cliff
+pub const PARTNER_VESTING_DURATION: i64 = 2 * 365 * 24 * 60 * 60;
+pub const PARTNER_CLIFF_DURATION: i64 = 182 * 24 * 60 * 60;
+
+#[program]
+pub mod solearn_vesting {
+    use super::*;
+
+    /// Create a cliff plus linear vesting schedule for a beneficiary and fund
+    /// its vault with the full amount from the authority's token account.
+    /// The cliff and duration follow the published terms of `category`.
+    pub fn create_vesting(
+        ctx: Context<CreateVesting>,
+        schedule_id: u64,
+        total_amount: u64,
+        start_timestamp: i64,
+        category: VestingCategory,
+        revocable: bool,
+    ) -> Result<()> {
+        // Validate schedule
+        if total_amount == 0 {
+            return Err(ErrorCode::InvalidVestingAmount.into());
+        }
+        let (cliff_duration, vesting_duration) = category.durations();
+
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        schedule.authority = ctx.accounts.authority.key();
+        schedule.beneficiary = ctx.accounts.beneficiary.key();
+        schedule.mint = ctx.accounts.mint.key();
+        schedule.vault = ctx.accounts.vault.key();
+        schedule.schedule_id = schedule_id;
+        schedule.total_amount = total_amount;
+        schedule.claimed_amount = 0;
+        schedule.start_timestamp = start_timestamp;
+        schedule.cliff_duration = cliff_duration;
+        schedule.vesting_duration = vesting_duration;
+        schedule.revocable = revocable;
+        schedule.revoked_at = 0;
+        schedule.bump = *ctx.bumps.get("vesting_schedule").unwrap();
+
+        // Fund the vault
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.funding_account.to_account_info(),
+            to: ctx.accounts.vault.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        token::transfer(cpi_ctx, total_amount)?;
+
+        msg!(
+            "Vesting schedule created for {}: {} tokens, cliff {}s, duration {}s",
+            schedule.beneficiary,
+            total_amount,
+            cliff_duration,
+            vesting_duration
+        );
+
+        Ok(())
+    }
+
+    /// Transfer everything vested so far that has not been claimed yet to
+    /// the beneficiary
+    pub fn claim_vested(ctx: Context<ClaimVested>) -> Result<()> {
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        let now = Clock::get()?.unix_timestamp;
+
+        let claimable = schedule.vested_amount(now).saturating_sub(schedule.claimed_amount);
+        if claimable == 0 {
+            return Err(ErrorCode::NothingToClaim.into());
+        }
+        schedule.claimed_amount += claimable;
+
+        transfer_from_vault(
+            schedule,
+            &ctx.accounts.vault,
+            &ctx.accounts.beneficiary_token_account,
+            &ctx.accounts.token_program,
+            claimable,
+        )?;
+
+        msg!("Claimed {} vested tokens for {}", claimable, schedule.beneficiary);
+
+        Ok(())
+    }
+
+    /// Stop a revocable schedule and return its unvested tokens to the
+    /// authority. Tokens vested up to now remain claimable.
+    pub fn revoke_unvested(ctx: Context<RevokeUnvested>) -> Result<()> {
+        let schedule = &mut ctx.accounts.vesting_schedule;
+        let now = Clock::get()?.unix_timestamp;
+
+        if !schedule.revocable {
+            return Err(ErrorCode::NotRevocable.into());
+        }
+        if schedule.revoked_at != 0 {
+            return Err(ErrorCode::AlreadyRevoked.into());
+        }
+
+        let vested = schedule.vested_amount(now);
+        let unvested = schedule.total_amount - vested;
+        schedule.total_amount = vested;
+        schedule.revoked_at = now;
+
+        if unvested > 0 {
+            transfer_from_vault(
+                schedule,
+                &ctx.accounts.vault,
+                &ctx.accounts.authority_token_account,
+                &ctx.accounts.token_program,
+                unvested,
+            )?;
+        }
+
+        msg!(
+            "Vesting schedule for {} revoked, returned {} unvested tokens",
+            schedule.beneficiary,
+            unvested
+        );
+
+        Ok(())
+    }
+}
+
+/// Transfer `amount` out of a schedule's vault, signed by the schedule PDA
+fn transfer_from_vault<'info>(
+    schedule: &Account<'info, VestingSchedule>,
+    vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let schedule_id = schedule.schedule_id.to_le_bytes();
+    let seeds = &[
+        b"vesting".as_ref(),
+        schedule.mint.as_ref(),
+        schedule.beneficiary.as_ref(),
+        schedule_id.as_ref(),
+        &[schedule.bump],
+    ];
+    let signer = &[&seeds[..]];
+
+    let cpi_accounts = Transfer {
+        from: vault.to_account_info(),
+        to: to.to_account_info(),
+        authority: schedule.to_account_info(),
+    };
+
+    let cpi_program = token_program.to_account_info();
+    let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+    token::transfer(cpi_ctx, amount)
+}
+
+#[derive(Accounts)]
+#[instruction(schedule_id: u64)]
+pub struct CreateVesting<'info> {
+    #[account(
+        init,
+        seeds = [b"vesting", mint.key().as_ref(), beneficiary.key().as_ref(), &schedule_id.to_le_bytes()],
+        bump,
+        payer = authority,
+        space = 8 + VestingSchedule::LEN
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(
+        init,
+        seeds = [b"vesting-vault", vesting_schedule.key().as_ref()],
+        bump,
+        payer = authority,
+        token::mint = mint,
+        token::authority = vesting_schedule,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The wallet that will be able to claim vested tokens
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        constraint = funding_account.mint == mint.key(),
+        constraint = funding_account.owner == authority.key(),
+    )]
+    pub funding_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVested<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_schedule.mint.as_ref(),
+            beneficiary.key().as_ref(),
+            &vesting_schedule.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_schedule.bump,
+        has_one = beneficiary @ ErrorCode::Unauthorized,
+        has_one = vault,
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = beneficiary_token_account.mint == vesting_schedule.mint,
+        constraint = beneficiary_token_account.owner == beneficiary.key(),
+    )]
+    pub beneficiary_token_account: Account<'info, TokenAccount>,
+
+    pub beneficiary: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeUnvested<'info> {
+    #[account(
+        mut,
+        seeds = [
+            b"vesting",
+            vesting_schedule.mint.as_ref(),
+            vesting_schedule.beneficiary.as_ref(),
+            &vesting_schedule.schedule_id.to_le_bytes(),
+        ],
+        bump = vesting_schedule.bump,
+        has_one = authority @ ErrorCode::Unauthorized,
+        has_one = vault,
+    )]
+    pub vesting_schedule: Account<'info, VestingSchedule>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = authority_token_account.mint == vesting_schedule.mint,
+        constraint = authority_token_account.owner == authority.key(),
+    )]
+    pub authority_token_account: Account<'info, TokenAccount>,
+
+    pub authority: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Allocation a schedule belongs to, which fixes its cliff and duration
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VestingCategory {
+    TeamAndAdvisors,
+    StrategicPartners,
+}
+
+impl VestingCategory {
+    /// Cliff and total vesting duration in seconds
+    pub fn durations(&self) -> (i64, i64) {
+        match self {
+            VestingCategory::TeamAndAdvisors => (TEAM_CLIFF_DURATION, TEAM_VESTING_DURATION),
+            VestingCategory::StrategicPartners => (PARTNER_CLIFF_DURATION, PARTNER_VESTING_DURATION),
+        }
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct VestingSchedule {
+    pub authority: Pubkey,             // 32 bytes
+    pub beneficiary: Pubkey,           // 32 bytes
+    pub mint: Pubkey,                  // 32 bytes
+    pub vault: Pubkey,                 // 32 bytes
+    pub schedule_id: u64,              // 8 bytes
+    pub total_amount: u64,             // 8 bytes
+    pub claimed_amount: u64,           // 8 bytes
+    pub start_timestamp: i64,          // 8 bytes
+    pub cliff_duration: i64,           // 8 bytes
+    pub vesting_duration: i64,         // 8 bytes
+    pub revocable: bool,               // 1 byte
+    pub revoked_at: i64,               // 8 bytes, 0 while not revoked
+    pub bump: u8,                      // 1 byte
+}
+
+impl VestingSchedule {
+    pub const LEN: usize = 32 + 32 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+
+    /// Amount vested at `now`: nothing before the cliff, then linear from
+    /// the start until the full amount at the end of the vesting duration.
+    /// A revoked schedule stays at what had vested when it was revoked.
+    pub fn vested_amount(&self, now: i64) -> u64 {
+        if self.revoked_at != 0 {
+            return self.total_amount;
+        }
+
+        let elapsed = now.saturating_sub(self.start_timestamp);
+        if elapsed < self.cliff_duration {
+            return 0;
+        }
+        if elapsed >= self.vesting_duration {
+            return self.total_amount;
+        }
+
+        let vested = u128::from(self.total_amount) * u128::try_from(elapsed).unwrap()
+            / u128::try_from(self.vesting_duration).unwrap();
+        u64::try_from(vested).unwrap()
+    }
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized access")]
+    Unauthorized,
+
+    #[msg("Vesting amount must be greater than zero")]
+    InvalidVestingAmount,
+
+    #[msg("No vested tokens to claim")]
+    NothingToClaim,
+
+    #[msg("Vesting schedule is not revocable")]
+    NotRevocable,
+
+    #[msg("Vesting schedule already revoked")]
+    AlreadyRevoked,
+}
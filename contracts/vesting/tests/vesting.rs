@@ -0,0 +1,334 @@
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use solana_program_test::{processor, tokio, BanksClientError, ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::Instruction,
+    native_token::LAMPORTS_PER_SOL,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction, system_program, sysvar,
+    transaction::Transaction,
+};
+use solearn_vesting::{
+    VestingCategory, VestingSchedule, PARTNER_CLIFF_DURATION, PARTNER_VESTING_DURATION,
+    TEAM_CLIFF_DURATION, TEAM_VESTING_DURATION,
+};
+
+const TOTAL_AMOUNT: u64 = 4_000_000;
+const YEAR: i64 = 365 * 24 * 60 * 60;
+
+struct Fixture {
+    context: ProgramTestContext,
+    authority: Keypair,
+    beneficiary: Keypair,
+    mint: Pubkey,
+    authority_token_account: Pubkey,
+    beneficiary_token_account: Pubkey,
+    start: i64,
+    now: i64,
+}
+
+fn funded_account() -> Account {
+    Account {
+        lamports: 10 * LAMPORTS_PER_SOL,
+        ..Account::default()
+    }
+}
+
+impl Fixture {
+    /// Send a transaction at the fixture's current time
+    async fn process(
+        &mut self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let recent_blockhash = self.context.get_new_latest_blockhash().await.unwrap();
+        let mut clock: Clock = self.context.banks_client.get_sysvar().await.unwrap();
+        clock.unix_timestamp = self.now;
+        self.context.set_sysvar(&clock);
+
+        let mut all_signers = vec![payer];
+        all_signers.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            all_signers.as_slice(),
+            recent_blockhash,
+        );
+        self.context.banks_client.process_transaction(transaction).await
+    }
+
+    async fn balance(&mut self, token_account: Pubkey) -> u64 {
+        let account = self
+            .context
+            .banks_client
+            .get_account(token_account)
+            .await
+            .unwrap()
+            .unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    async fn schedule(&mut self, schedule_id: u64) -> VestingSchedule {
+        let address = self.vesting_schedule(schedule_id);
+        let account = self
+            .context
+            .banks_client
+            .get_account(address)
+            .await
+            .unwrap()
+            .unwrap();
+        VestingSchedule::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn vesting_schedule(&self, schedule_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[
+                b"vesting",
+                self.mint.as_ref(),
+                self.beneficiary.pubkey().as_ref(),
+                &schedule_id.to_le_bytes(),
+            ],
+            &solearn_vesting::id(),
+        )
+        .0
+    }
+
+    fn vault(&self, schedule_id: u64) -> Pubkey {
+        Pubkey::find_program_address(
+            &[b"vesting-vault", self.vesting_schedule(schedule_id).as_ref()],
+            &solearn_vesting::id(),
+        )
+        .0
+    }
+
+    async fn create_vesting(
+        &mut self,
+        schedule_id: u64,
+        category: VestingCategory,
+        revocable: bool,
+    ) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vesting::id(),
+            accounts: solearn_vesting::accounts::CreateVesting {
+                vesting_schedule: self.vesting_schedule(schedule_id),
+                vault: self.vault(schedule_id),
+                mint: self.mint,
+                beneficiary: self.beneficiary.pubkey(),
+                funding_account: self.authority_token_account,
+                authority: authority.pubkey(),
+                system_program: system_program::id(),
+                token_program: spl_token::id(),
+                rent: sysvar::rent::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vesting::instruction::CreateVesting {
+                schedule_id,
+                total_amount: TOTAL_AMOUNT,
+                start_timestamp: self.start,
+                category,
+                revocable,
+            }
+            .data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+
+    async fn claim_vested(&mut self, schedule_id: u64) -> Result<(), BanksClientError> {
+        let beneficiary = self.beneficiary.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vesting::id(),
+            accounts: solearn_vesting::accounts::ClaimVested {
+                vesting_schedule: self.vesting_schedule(schedule_id),
+                vault: self.vault(schedule_id),
+                beneficiary_token_account: self.beneficiary_token_account,
+                beneficiary: beneficiary.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vesting::instruction::ClaimVested {}.data(),
+        };
+        self.process(&[instruction], &beneficiary, &[]).await
+    }
+
+    async fn revoke_unvested(&mut self, schedule_id: u64) -> Result<(), BanksClientError> {
+        let authority = self.authority.insecure_clone();
+        let instruction = Instruction {
+            program_id: solearn_vesting::id(),
+            accounts: solearn_vesting::accounts::RevokeUnvested {
+                vesting_schedule: self.vesting_schedule(schedule_id),
+                vault: self.vault(schedule_id),
+                authority_token_account: self.authority_token_account,
+                authority: authority.pubkey(),
+                token_program: spl_token::id(),
+            }
+            .to_account_metas(None),
+            data: solearn_vesting::instruction::RevokeUnvested {}.data(),
+        };
+        self.process(&[instruction], &authority, &[]).await
+    }
+}
+
+/// Start a validator with a LEARN mint, an authority holding enough tokens
+/// for two schedules and an empty beneficiary token account
+async fn setup() -> Fixture {
+    let mut program_test = ProgramTest::new(
+        "solearn_vesting",
+        solearn_vesting::id(),
+        processor!(solearn_vesting::entry),
+    );
+
+    let authority = Keypair::new();
+    let beneficiary = Keypair::new();
+    program_test.add_account(authority.pubkey(), funded_account());
+    program_test.add_account(beneficiary.pubkey(), funded_account());
+
+    let context = program_test.start_with_context().await;
+    let now = context.banks_client.get_sysvar::<Clock>().await.unwrap().unix_timestamp;
+
+    let mint = Keypair::new();
+    let authority_token_account = Keypair::new();
+    let beneficiary_token_account = Keypair::new();
+    let mut fixture = Fixture {
+        context,
+        authority: authority.insecure_clone(),
+        beneficiary: beneficiary.insecure_clone(),
+        mint: mint.pubkey(),
+        authority_token_account: authority_token_account.pubkey(),
+        beneficiary_token_account: beneficiary_token_account.pubkey(),
+        start: now,
+        now,
+    };
+
+    let rent = fixture.context.banks_client.get_rent().await.unwrap();
+    let mut instructions = vec![
+        system_instruction::create_account(
+            &authority.pubkey(),
+            &mint.pubkey(),
+            rent.minimum_balance(spl_token::state::Mint::LEN),
+            spl_token::state::Mint::LEN as u64,
+            &spl_token::id(),
+        ),
+        spl_token::instruction::initialize_mint(&spl_token::id(), &mint.pubkey(), &authority.pubkey(), None, 6)
+            .unwrap(),
+    ];
+    for (token_account, owner) in [
+        (&authority_token_account, authority.pubkey()),
+        (&beneficiary_token_account, beneficiary.pubkey()),
+    ] {
+        instructions.extend([
+            system_instruction::create_account(
+                &authority.pubkey(),
+                &token_account.pubkey(),
+                rent.minimum_balance(spl_token::state::Account::LEN),
+                spl_token::state::Account::LEN as u64,
+                &spl_token::id(),
+            ),
+            spl_token::instruction::initialize_account(
+                &spl_token::id(),
+                &token_account.pubkey(),
+                &mint.pubkey(),
+                &owner,
+            )
+            .unwrap(),
+        ]);
+    }
+    instructions.push(
+        spl_token::instruction::mint_to(
+            &spl_token::id(),
+            &mint.pubkey(),
+            &authority_token_account.pubkey(),
+            &authority.pubkey(),
+            &[],
+            2 * TOTAL_AMOUNT,
+        )
+        .unwrap(),
+    );
+    fixture
+        .process(
+            &instructions,
+            &authority,
+            &[&mint, &authority_token_account, &beneficiary_token_account],
+        )
+        .await
+        .unwrap();
+
+    fixture
+}
+
+#[tokio::test]
+async fn team_schedule_vests_linearly_after_the_cliff() {
+    let mut fixture = setup().await;
+    fixture
+        .create_vesting(1, VestingCategory::TeamAndAdvisors, false)
+        .await
+        .unwrap();
+
+    // The schedule takes the team terms and the vault holds the full amount
+    let schedule = fixture.schedule(1).await;
+    assert_eq!(schedule.cliff_duration, TEAM_CLIFF_DURATION);
+    assert_eq!(schedule.vesting_duration, TEAM_VESTING_DURATION);
+    let vault = fixture.vault(1);
+    assert_eq!(fixture.balance(vault).await, TOTAL_AMOUNT);
+
+    // Nothing is claimable before the cliff
+    fixture.now = fixture.start + TEAM_CLIFF_DURATION - 1;
+    assert!(fixture.claim_vested(1).await.is_err());
+
+    // At the cliff a year's worth has vested, and it can only be claimed once
+    fixture.now = fixture.start + TEAM_CLIFF_DURATION;
+    fixture.claim_vested(1).await.unwrap();
+    let beneficiary_token_account = fixture.beneficiary_token_account;
+    assert_eq!(fixture.balance(beneficiary_token_account).await, TOTAL_AMOUNT / 4);
+    assert!(fixture.claim_vested(1).await.is_err());
+
+    // Vesting continues linearly until the end of the duration
+    fixture.now = fixture.start + 2 * YEAR;
+    fixture.claim_vested(1).await.unwrap();
+    assert_eq!(fixture.balance(beneficiary_token_account).await, TOTAL_AMOUNT / 2);
+
+    fixture.now = fixture.start + TEAM_VESTING_DURATION + YEAR;
+    fixture.claim_vested(1).await.unwrap();
+    assert_eq!(fixture.balance(beneficiary_token_account).await, TOTAL_AMOUNT);
+    assert_eq!(fixture.balance(vault).await, 0);
+    assert_eq!(fixture.schedule(1).await.claimed_amount, TOTAL_AMOUNT);
+
+    // Irrevocable schedules cannot be revoked
+    assert!(fixture.revoke_unvested(1).await.is_err());
+}
+
+#[tokio::test]
+async fn revoke_returns_unvested_and_keeps_vested_claimable() {
+    let mut fixture = setup().await;
+    fixture
+        .create_vesting(1, VestingCategory::StrategicPartners, true)
+        .await
+        .unwrap();
+    let schedule = fixture.schedule(1).await;
+    assert_eq!(schedule.cliff_duration, PARTNER_CLIFF_DURATION);
+    assert_eq!(schedule.vesting_duration, PARTNER_VESTING_DURATION);
+
+    // Halfway through the partner schedule the unvested half goes back
+    fixture.now = fixture.start + PARTNER_VESTING_DURATION / 2;
+    fixture.revoke_unvested(1).await.unwrap();
+    let authority_token_account = fixture.authority_token_account;
+    assert_eq!(
+        fixture.balance(authority_token_account).await,
+        TOTAL_AMOUNT + TOTAL_AMOUNT / 2
+    );
+    assert!(fixture.revoke_unvested(1).await.is_err());
+
+    // The vested half stays claimable and nothing more vests afterwards
+    fixture.now = fixture.start + PARTNER_VESTING_DURATION + YEAR;
+    fixture.claim_vested(1).await.unwrap();
+    let beneficiary_token_account = fixture.beneficiary_token_account;
+    assert_eq!(fixture.balance(beneficiary_token_account).await, TOTAL_AMOUNT / 2);
+    let vault = fixture.vault(1);
+    assert_eq!(fixture.balance(vault).await, 0);
+    assert!(fixture.claim_vested(1).await.is_err());
+}
@@ -0,0 +1,469 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use solearn_token::MintConfig;
+
+declare_id!("LearnVoteEscrowProgram111111111111111111111");
+
+#[program]
+pub mod solearn_vote_escrow {
+    use super::*;
+
+    /// Create the vote escrow for a LEARN mint. A lock of `max_lock_duration`
+    /// starts with voting power equal to its amount, decaying linearly to
+    /// zero at unlock. Only the authority of the mint can create its escrow.
+    pub fn initialize_escrow(
+        ctx: Context<InitializeEscrow>,
+        min_lock_duration: i64,
+        max_lock_duration: i64,
+    ) -> Result<()> {
+        if min_lock_duration <= 0 || max_lock_duration < min_lock_duration {
+            return Err(ErrorCode::InvalidLockDuration.into());
+        }
+
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.mint = ctx.accounts.mint.key();
+        escrow.vault = ctx.accounts.vault.key();
+        escrow.total_locked = 0;
+        escrow.min_lock_duration = min_lock_duration;
+        escrow.max_lock_duration = max_lock_duration;
+        escrow.bump = *ctx.bumps.get("escrow").unwrap();
+
+        msg!("Vote escrow initialized for mint {}", escrow.mint);
+
+        Ok(())
+    }
+
+    /// Lock LEARN until `unlock_at`, which must be between the escrow's
+    /// minimum and maximum lock duration from now
+    pub fn create_lock(ctx: Context<CreateLock>, amount: u64, unlock_at: i64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut ctx.accounts.escrow;
+        escrow.validate_unlock_at(now, unlock_at)?;
+
+        transfer_to_vault(
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        let lock = &mut ctx.accounts.lock;
+        lock.escrow = escrow.key();
+        lock.owner = ctx.accounts.owner.key();
+        lock.amount = amount;
+        lock.locked_at = now;
+        lock.unlock_at = unlock_at;
+        lock.updated_at = now;
+        lock.bump = *ctx.bumps.get("lock").unwrap();
+        escrow.total_locked = escrow.total_locked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LockUpdated {
+            lock: lock.key(),
+            owner: lock.owner,
+            amount: lock.amount,
+            unlock_at,
+        });
+
+        msg!("Locked {} tokens for {} until {}", amount, lock.owner, unlock_at);
+
+        Ok(())
+    }
+
+    /// Add LEARN to an unexpired lock without changing its unlock time. The
+    /// lock has no voting power at earlier timestamps afterwards.
+    pub fn increase_lock_amount(ctx: Context<UpdateLock>, amount: u64) -> Result<()> {
+        if amount == 0 {
+            return Err(ErrorCode::InvalidAmount.into());
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut ctx.accounts.escrow;
+        let lock = &mut ctx.accounts.lock;
+        if now >= lock.unlock_at {
+            return Err(ErrorCode::LockExpired.into());
+        }
+
+        transfer_to_vault(
+            &ctx.accounts.owner_token_account,
+            &ctx.accounts.vault,
+            &ctx.accounts.owner,
+            &ctx.accounts.token_program,
+            amount,
+        )?;
+
+        lock.amount = lock.amount.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+        lock.updated_at = now;
+        escrow.total_locked = escrow.total_locked.checked_add(amount).ok_or(ErrorCode::MathOverflow)?;
+
+        emit!(LockUpdated {
+            lock: lock.key(),
+            owner: lock.owner,
+            amount: lock.amount,
+            unlock_at: lock.unlock_at,
+        });
+
+        msg!("Increased lock of {} by {} tokens", lock.owner, amount);
+
+        Ok(())
+    }
+
+    /// Move the unlock time of a lock later. Expired locks can be relocked
+    /// this way instead of being withdrawn. The lock has no voting power at
+    /// earlier timestamps afterwards.
+    pub fn extend_lock(ctx: Context<ExtendLock>, unlock_at: i64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &ctx.accounts.escrow;
+        let lock = &mut ctx.accounts.lock;
+
+        if unlock_at <= lock.unlock_at {
+            return Err(ErrorCode::InvalidLockDuration.into());
+        }
+        escrow.validate_unlock_at(now, unlock_at)?;
+
+        lock.unlock_at = unlock_at;
+        lock.updated_at = now;
+
+        emit!(LockUpdated {
+            lock: lock.key(),
+            owner: lock.owner,
+            amount: lock.amount,
+            unlock_at,
+        });
+
+        msg!("Extended lock of {} until {}", lock.owner, unlock_at);
+
+        Ok(())
+    }
+
+    /// Withdraw an expired lock and close it
+    pub fn withdraw(ctx: Context<Withdraw>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let escrow = &mut ctx.accounts.escrow;
+        let lock = &ctx.accounts.lock;
+
+        if now < lock.unlock_at {
+            return Err(ErrorCode::LockNotExpired.into());
+        }
+
+        let seeds = &[b"escrow".as_ref(), escrow.mint.as_ref(), &[escrow.bump]];
+        let signer = &[&seeds[..]];
+
+        let cpi_accounts = Transfer {
+            from: ctx.accounts.vault.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: escrow.to_account_info(),
+        };
+
+        let cpi_program = ctx.accounts.token_program.to_account_info();
+        let cpi_ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer);
+
+        token::transfer(cpi_ctx, lock.amount)?;
+
+        escrow.total_locked -= lock.amount;
+
+        msg!("Withdrew {} tokens for {}", lock.amount, lock.owner);
+
+        Ok(())
+    }
+
+    /// Return the voting power of a wallet's lock at `timestamp`
+    pub fn get_voting_power(ctx: Context<GetVotingPower>, timestamp: i64) -> Result<u64> {
+        ctx.accounts.lock.voting_power_at(&ctx.accounts.escrow, timestamp)
+    }
+}
+
+/// Transfer `amount` from the owner's token account into the escrow vault
+fn transfer_to_vault<'info>(
+    from: &Account<'info, TokenAccount>,
+    vault: &Account<'info, TokenAccount>,
+    owner: &Signer<'info>,
+    token_program: &Program<'info, Token>,
+    amount: u64,
+) -> Result<()> {
+    let cpi_accounts = Transfer {
+        from: from.to_account_info(),
+        to: vault.to_account_info(),
+        authority: owner.to_account_info(),
+    };
+
+    token::transfer(CpiContext::new(token_program.to_account_info(), cpi_accounts), amount)
+}
+
+#[derive(Accounts)]
+pub struct InitializeEscrow<'info> {
+    #[account(
+        init,
+        seeds = [b"escrow", mint.key().as_ref()],
+        bump,
+        payer = payer,
+        space = 8 + Escrow::LEN
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        init,
+        seeds = [b"escrow-vault", escrow.key().as_ref()],
+        bump,
+        payer = payer,
+        token::mint = mint,
+        token::authority = escrow,
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    pub mint: Account<'info, Mint>,
+
+    /// The solearn_token MintConfig of `mint`, passed when the mint is
+    /// managed by solearn_token
+    #[account(
+        seeds = [b"mint-config", mint.key().as_ref()],
+        bump,
+        seeds::program = solearn_token::ID,
+    )]
+    pub mint_config: Option<Account<'info, MintConfig>>,
+
+    /// The SPL mint authority, or the MintConfig authority of a mint
+    /// managed by solearn_token
+    #[account(
+        constraint = solearn_token::controls_mint(&mint, mint_config.as_deref(), &authority.key())
+            @ ErrorCode::Unauthorized,
+    )]
+    pub authority: Signer<'info>,
+
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct CreateLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.mint.as_ref()],
+        bump = escrow.bump,
+        has_one = vault,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        init,
+        seeds = [b"lock", escrow.key().as_ref(), owner.key().as_ref()],
+        bump,
+        payer = owner,
+        space = 8 + Lock::LEN
+    )]
+    pub lock: Account<'info, Lock>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == escrow.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateLock<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.mint.as_ref()],
+        bump = escrow.bump,
+        has_one = vault,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"lock", escrow.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub lock: Account<'info, Lock>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == escrow.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct ExtendLock<'info> {
+    #[account(
+        seeds = [b"escrow", escrow.mint.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"lock", escrow.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+    )]
+    pub lock: Account<'info, Lock>,
+
+    pub owner: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(
+        mut,
+        seeds = [b"escrow", escrow.mint.as_ref()],
+        bump = escrow.bump,
+        has_one = vault,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        mut,
+        seeds = [b"lock", escrow.key().as_ref(), owner.key().as_ref()],
+        bump = lock.bump,
+        has_one = owner @ ErrorCode::Unauthorized,
+        close = owner,
+    )]
+    pub lock: Account<'info, Lock>,
+
+    #[account(mut)]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        constraint = owner_token_account.mint == escrow.mint,
+        constraint = owner_token_account.owner == owner.key(),
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct GetVotingPower<'info> {
+    #[account(
+        seeds = [b"escrow", escrow.mint.as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    #[account(
+        seeds = [b"lock", escrow.key().as_ref(), lock.owner.as_ref()],
+        bump = lock.bump,
+    )]
+    pub lock: Account<'info, Lock>,
+}
+
+#[account]
+#[derive(Default)]
+pub struct Escrow {
+    pub mint: Pubkey,                  // 32 bytes
+    pub vault: Pubkey,                 // 32 bytes
+    pub total_locked: u64,             // 8 bytes
+    pub min_lock_duration: i64,        // 8 bytes
+    pub max_lock_duration: i64,        // 8 bytes
+    pub bump: u8,                      // 1 byte
+}
+
+impl Escrow {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 1;
+
+    /// Check that `unlock_at` is within the allowed lock durations from `now`
+    pub fn validate_unlock_at(&self, now: i64, unlock_at: i64) -> Result<()> {
+        let duration = unlock_at.checked_sub(now).ok_or(ErrorCode::MathOverflow)?;
+        if duration < self.min_lock_duration || duration > self.max_lock_duration {
+            return Err(ErrorCode::InvalidLockDuration.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[account]
+#[derive(Default)]
+pub struct Lock {
+    pub escrow: Pubkey,                // 32 bytes
+    pub owner: Pubkey,                 // 32 bytes
+    pub amount: u64,                   // 8 bytes
+    pub locked_at: i64,                // 8 bytes
+    pub unlock_at: i64,                // 8 bytes
+    pub updated_at: i64,               // 8 bytes, last change of the amount or unlock time
+    pub bump: u8,                      // 1 byte
+}
+
+impl Lock {
+    pub const LEN: usize = 32 + 32 + 8 + 8 + 8 + 8 + 1;
+
+    /// Voting power at `timestamp`: the locked amount scaled by the time left
+    /// until unlock over the escrow's maximum lock duration, rounded down.
+    /// Zero once the lock has expired, and before its last change since only
+    /// the current amount and unlock time are kept.
+    pub fn voting_power_at(&self, escrow: &Escrow, timestamp: i64) -> Result<u64> {
+        if timestamp < self.updated_at || timestamp >= self.unlock_at {
+            return Ok(0);
+        }
+
+        let remaining = (self.unlock_at - timestamp).min(escrow.max_lock_duration) as u128;
+        let power = u128::from(self.amount)
+            .checked_mul(remaining)
+            .ok_or(ErrorCode::MathOverflow)?
+            / escrow.max_lock_duration as u128;
+
+        u64::try_from(power).map_err(|_| ErrorCode::MathOverflow.into())
+    }
+}
+
+#[event]
+pub struct LockUpdated {
+    pub lock: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub unlock_at: i64,
+}
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Unauthorized")]
+    Unauthorized,
+
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+
+    #[msg("Lock duration is outside the allowed range")]
+    InvalidLockDuration,
+
+    #[msg("Lock has expired")]
+    LockExpired,
+
+    #[msg("Lock has not expired yet")]
+    LockNotExpired,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+}